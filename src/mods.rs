@@ -7,4 +7,34 @@ pub(crate) mod tokenaize;
 pub(crate) mod traits;
 pub(crate) mod vec_man;
 pub(crate) mod statistics;
-pub(crate) mod lazy_str;
\ No newline at end of file
+pub(crate) mod lazy_str;
+pub(crate) mod corpus;
+pub(crate) mod cooccurrence;
+pub(crate) mod corpus_stats;
+pub(crate) mod features;
+pub(crate) mod get_html;
+pub(crate) mod tier_compare;
+pub(crate) mod topic_model;
+pub(crate) mod embedding;
+pub(crate) mod main_content;
+pub(crate) mod text_streams;
+pub(crate) mod heading_outline;
+pub(crate) mod metadata;
+pub(crate) mod structured_data;
+pub(crate) mod images;
+pub(crate) mod performance;
+pub(crate) mod headers;
+pub(crate) mod compression;
+pub(crate) mod accessibility;
+pub(crate) mod mobile;
+pub(crate) mod indexability;
+pub(crate) mod duplicates;
+pub(crate) mod content_gap;
+pub(crate) mod text_quality;
+pub(crate) mod intent;
+pub(crate) mod dates;
+pub(crate) mod eeat;
+pub(crate) mod freshness;
+pub(crate) mod meta_suggest;
+pub(crate) mod summarize;
+pub(crate) mod site_architecture;
\ No newline at end of file
@@ -0,0 +1,494 @@
+use crate::mods::main_content;
+use crate::mods::traits::Formatter;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+// タップターゲットの最小サイズ (px)
+pub const MIN_TAP_TARGET: f64 = 48.0;
+// 通常の文字に必要なコントラスト比 (WCAG AA)
+pub const MIN_CONTRAST: f64 = 4.5;
+
+// 内容の分からないリンクテキスト
+const NON_DESCRIPTIVE_TEXTS: [&str; 14] = [
+    "こちら",
+    "ここ",
+    "こちらをクリック",
+    "ここをクリック",
+    "詳しくはこちら",
+    "詳細はこちら",
+    "詳細",
+    "もっと見る",
+    "続きを読む",
+    "click here",
+    "here",
+    "more",
+    "read more",
+    "link",
+];
+
+// 名前の要らない入力欄
+const NAMELESS_INPUT_TYPES: [&str; 1] = ["hidden"];
+
+// 指摘の種類
+#[derive(Debug, Clone, PartialEq)]
+pub enum A11yIssue {
+    MissingName,
+    UnlabeledControl,
+    NonDescriptiveLink(String),
+    SmallTapTarget {
+        width: f64,
+        height: f64,
+    },
+    LowContrast {
+        foreground: String,
+        background: String,
+        ratio: f64,
+    },
+}
+
+// 指摘1つ分 (selectorで要素を特定する)
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub selector: String,
+    pub tag: String,
+    pub role: String, // role属性 (なければタグから決まる役割)
+    pub name: Option<String>,
+    pub issue: A11yIssue,
+}
+
+// 操作できる要素1つ分の情報
+#[derive(Debug, Clone, Default)]
+pub struct Control {
+    pub selector: String,
+    pub tag: String,
+    pub role: String,
+    pub name: Option<String>,
+    pub labelled: bool, // label要素やaria属性で名前が付いているか
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct A11yReport {
+    pub controls: Vec<Control>,
+    pub findings: Vec<Finding>,
+}
+
+fn normalize(text: &str) -> String {
+    text.replace_ws().format_ws()
+}
+
+fn non_empty(text: String) -> Option<String> {
+    let text = normalize(&text);
+    (!text.is_empty()).then_some(text)
+}
+
+// 要素を特定するCSSセレクタ (idがあればそこで止める)
+pub fn selector_of(element: &ElementRef) -> String {
+    let mut parts = Vec::new();
+    let mut current = Some(*element);
+    while let Some(node) = current {
+        let value = node.value();
+        if let Some(id) = value.id() {
+            parts.push(format!("{}#{}", value.name(), id));
+            break;
+        }
+        let index = node
+            .prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .filter(|sibling| sibling.value().name() == value.name())
+            .count();
+        if index == 0 {
+            parts.push(value.name().to_string());
+        } else {
+            parts.push(format!("{}:nth-of-type({})", value.name(), index + 1));
+        }
+        current = node.parent().and_then(ElementRef::wrap);
+    }
+    parts.reverse();
+    parts.join(" > ")
+}
+
+// style属性をプロパティ名と値の組にする
+pub fn parse_style(style: &str) -> HashMap<String, String> {
+    style
+        .split(';')
+        .filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            let value = value.trim().trim_end_matches("!important").trim();
+            Some((name.trim().to_lowercase(), value.to_lowercase()))
+        })
+        .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+        .collect()
+}
+
+pub fn inline_style(element: &ElementRef) -> HashMap<String, String> {
+    parse_style(element.value().attr("style").unwrap_or(""))
+}
+
+// "16px" などの長さをpxで返す (相対単位は判定しない)
+pub fn parse_px(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Some(number) = value.strip_suffix("px") {
+        return number.trim().parse().ok();
+    }
+    if let Some(number) = value.strip_suffix("pt") {
+        return number.trim().parse::<f64>().ok().map(|pt| pt * 4.0 / 3.0);
+    }
+    (value == "0").then_some(0.0)
+}
+
+// #rgb / #rrggbb / rgb() / 一部の色名をRGBにする
+pub fn parse_color(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.trim().to_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        // 以降はバイト単位で切り出すので、16進数の文字以外は先に除く
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let hex: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some((channel(0)?, channel(2)?, channel(4)?));
+    }
+    if let Some(args) = value
+        .strip_prefix("rgb(")
+        .or(value.strip_prefix("rgba("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let channels: Vec<u8> = args
+            .split([',', ' ', '/'])
+            .filter(|part| !part.is_empty())
+            .take(3)
+            .filter_map(|part| part.parse::<f64>().ok().map(|v| v.clamp(0.0, 255.0) as u8))
+            .collect();
+        return (channels.len() == 3).then(|| (channels[0], channels[1], channels[2]));
+    }
+    match value.as_str() {
+        "white" => Some((255, 255, 255)),
+        "black" => Some((0, 0, 0)),
+        "gray" | "grey" => Some((128, 128, 128)),
+        "silver" => Some((192, 192, 192)),
+        "lightgray" | "lightgrey" => Some((211, 211, 211)),
+        "red" => Some((255, 0, 0)),
+        "yellow" => Some((255, 255, 0)),
+        "blue" => Some((0, 0, 255)),
+        _ => None,
+    }
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |channel: u8| {
+        let c = channel as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+// WCAGのコントラスト比 (1～21)
+pub fn contrast_ratio(foreground: (u8, u8, u8), background: (u8, u8, u8)) -> f64 {
+    let (a, b) = (
+        relative_luminance(foreground),
+        relative_luminance(background),
+    );
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+// 自身か祖先のstyle属性から値を探す
+fn inherited_style(element: &ElementRef, properties: &[&str]) -> Option<String> {
+    std::iter::once(*element)
+        .chain(element.ancestors().filter_map(ElementRef::wrap))
+        .find_map(|node| {
+            let style = inline_style(&node);
+            properties
+                .iter()
+                .find_map(|property| style.get(*property).cloned())
+        })
+}
+
+fn is_control(element: &ElementRef) -> bool {
+    let value = element.value();
+    match value.name() {
+        "button" | "select" | "textarea" => true,
+        "input" => !NAMELESS_INPUT_TYPES.contains(&value.attr("type").unwrap_or("text")),
+        "a" => value.attr("href").is_some(),
+        _ => value.attr("role") == Some("button"),
+    }
+}
+
+// role属性がなければタグとtype属性から役割を決める
+fn role_of(element: &ElementRef) -> String {
+    let value = element.value();
+    if let Some(role) = value
+        .attr("role")
+        .and_then(|role| role.split_whitespace().next())
+    {
+        return role.to_lowercase();
+    }
+    let role = match value.name() {
+        "button" => "button",
+        "a" if value.attr("href").is_some() => "link",
+        "select" => "combobox",
+        "textarea" => "textbox",
+        "input" => match value.attr("type").unwrap_or("text").to_lowercase().as_str() {
+            "button" | "submit" | "reset" | "image" => "button",
+            "checkbox" => "checkbox",
+            "radio" => "radio",
+            "range" => "slider",
+            _ => "textbox",
+        },
+        _ => "",
+    };
+    role.to_string()
+}
+
+fn is_form_control(element: &ElementRef) -> bool {
+    matches!(element.value().name(), "input" | "select" | "textarea")
+}
+
+// 内部のテキストと画像のalt
+pub fn content_text(element: &ElementRef) -> String {
+    let alts: Vec<&str> = element
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| child.value().name() == "img")
+        .filter_map(|image| image.value().attr("alt"))
+        .collect();
+    format!("{} {}", main_content::visible_text(element), alts.join(" "))
+}
+
+struct NameContext {
+    texts_by_id: HashMap<String, String>,
+    labels_for: HashMap<String, String>,
+}
+
+impl NameContext {
+    fn new(document: &Html) -> Self {
+        let with_id = Selector::parse("[id]").unwrap();
+        let label = Selector::parse("label[for]").unwrap();
+        NameContext {
+            texts_by_id: document
+                .select(&with_id)
+                .map(|element| {
+                    (
+                        element.value().id().unwrap_or("").to_string(),
+                        main_content::visible_text(&element),
+                    )
+                })
+                .collect(),
+            labels_for: document
+                .select(&label)
+                .map(|element| {
+                    (
+                        element.value().attr("for").unwrap_or("").to_string(),
+                        content_text(&element),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    // ラベル由来の名前 (aria-labelledby, aria-label, label要素)
+    fn label_name(&self, element: &ElementRef) -> Option<String> {
+        let value = element.value();
+        if let Some(ids) = value.attr("aria-labelledby") {
+            let text: Vec<&str> = ids
+                .split_whitespace()
+                .filter_map(|id| self.texts_by_id.get(id).map(String::as_str))
+                .collect();
+            if let Some(name) = non_empty(text.join(" ")) {
+                return Some(name);
+            }
+        }
+        if let Some(name) = value
+            .attr("aria-label")
+            .and_then(|l| non_empty(l.to_string()))
+        {
+            return Some(name);
+        }
+        if !is_form_control(element) {
+            return None;
+        }
+        if let Some(name) = value
+            .id()
+            .and_then(|id| self.labels_for.get(id))
+            .and_then(|text| non_empty(text.clone()))
+        {
+            return Some(name);
+        }
+        element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|ancestor| ancestor.value().name() == "label")
+            .and_then(|label| non_empty(content_text(&label)))
+    }
+
+    // アクセシブルな名前を求める (見つからなければNone)
+    fn accessible_name(&self, element: &ElementRef) -> Option<String> {
+        if let Some(name) = self.label_name(element) {
+            return Some(name);
+        }
+        let value = element.value();
+        let input_type = value.attr("type").unwrap_or("text").to_lowercase();
+        let own = match (value.name(), input_type.as_str()) {
+            ("input", "submit" | "reset" | "button") => {
+                // submit/resetは値が無くてもブラウザ既定の名前が付く
+                value
+                    .attr("value")
+                    .map(str::to_string)
+                    .or((input_type != "button").then(|| input_type.clone()))
+            }
+            ("input", "image") => value.attr("alt").map(str::to_string),
+            ("input" | "select" | "textarea", _) => None,
+            _ => Some(content_text(element)),
+        };
+        own.and_then(non_empty)
+            .or(value.attr("title").and_then(|t| non_empty(t.to_string())))
+            .or(value
+                .attr("placeholder")
+                .and_then(|p| non_empty(p.to_string())))
+    }
+}
+
+// style属性で指定された大きさが小さすぎないか
+fn small_target(element: &ElementRef) -> Option<A11yIssue> {
+    let style = inline_style(element);
+    let size = |property: &str| style.get(property).and_then(|value| parse_px(value));
+    let width = size("width").or(size("min-width"));
+    let height = size("height").or(size("min-height"));
+    let too_small = |v: Option<f64>| v.is_some_and(|v| v < MIN_TAP_TARGET);
+    (too_small(width) || too_small(height)).then(|| A11yIssue::SmallTapTarget {
+        width: width.unwrap_or(0.0),
+        height: height.unwrap_or(0.0),
+    })
+}
+
+// 文字色と背景色が両方分かる場合だけコントラストを調べる
+fn low_contrast(element: &ElementRef) -> Option<A11yIssue> {
+    let foreground = inherited_style(element, &["color"])?;
+    let background = inherited_style(element, &["background-color", "background"])?;
+    let ratio = contrast_ratio(parse_color(&foreground)?, parse_color(&background)?);
+    (ratio < MIN_CONTRAST).then_some(A11yIssue::LowContrast {
+        foreground,
+        background,
+        ratio,
+    })
+}
+
+pub fn audit_html(html_content: &str) -> A11yReport {
+    let document = Html::parse_document(html_content);
+    let context = NameContext::new(&document);
+    let mut report = A11yReport::default();
+
+    for element in document.tree.nodes().filter_map(ElementRef::wrap) {
+        if main_content::is_hidden(&element) {
+            continue;
+        }
+        let selector = selector_of(&element);
+        let tag = element.value().name().to_string();
+        let role = role_of(&element);
+        let mut push = |name: &Option<String>, issue: A11yIssue| {
+            report.findings.push(Finding {
+                selector: selector.clone(),
+                tag: tag.clone(),
+                role: role.clone(),
+                name: name.clone(),
+                issue,
+            })
+        };
+
+        // 文字を含む要素のコントラスト
+        let has_text = element.children().any(|child| {
+            child
+                .value()
+                .as_text()
+                .is_some_and(|t| !t.trim().is_empty())
+        });
+        if has_text && element.value().attr("style").is_some() {
+            if let Some(issue) = low_contrast(&element) {
+                push(&None, issue);
+            }
+        }
+
+        if !is_control(&element) {
+            continue;
+        }
+        let name = context.accessible_name(&element);
+        let labelled = context.label_name(&element).is_some();
+        if name.is_none() {
+            push(&None, A11yIssue::MissingName);
+        }
+        // placeholderやtitleだけではラベルとして不十分
+        if is_form_control(&element) && !labelled {
+            let input_type = element.value().attr("type").unwrap_or("text");
+            if !matches!(input_type, "submit" | "reset" | "button" | "image") {
+                push(&name, A11yIssue::UnlabeledControl);
+            }
+        }
+        if tag == "a" {
+            if let Some(text) = &name {
+                if NON_DESCRIPTIVE_TEXTS.contains(&text.to_lowercase().as_str()) {
+                    push(&name, A11yIssue::NonDescriptiveLink(text.clone()));
+                }
+            }
+        }
+        if let Some(issue) = small_target(&element) {
+            push(&name, issue);
+        }
+        report.controls.push(Control {
+            selector,
+            tag,
+            role,
+            name,
+            labelled,
+        });
+    }
+    report
+}
+
+pub fn audit(file_path: &str) -> A11yReport {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    audit_html(&html_content)
+}
+
+impl A11yReport {
+    // 条件に合う指摘の数 (例: |issue| matches!(issue, A11yIssue::MissingName))
+    pub fn count(&self, matches: fn(&A11yIssue) -> bool) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| matches(&finding.issue))
+            .count()
+    }
+
+    // ボタン (button要素、type=submit等のinput、role=button) に名前が付いているか
+    pub fn buttons_named(&self) -> bool {
+        !self
+            .findings
+            .iter()
+            .any(|finding| finding.issue == A11yIssue::MissingName && finding.role == "button")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_hex() {
+        assert_eq!(parse_color("#fff"), Some((255, 255, 255)));
+        assert_eq!(parse_color("#1A2b3C"), Some((0x1a, 0x2b, 0x3c)));
+    }
+
+    // 16進数以外の文字 (マルチバイト文字を含む) は色として扱わない
+    #[test]
+    fn parse_color_non_ascii() {
+        assert_eq!(parse_color("#日"), None);
+        assert_eq!(parse_color("#a日bc"), None);
+        assert_eq!(parse_color("#ggg"), None);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::mods::headers;
+use crate::mods::images;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use scraper::{Html, Selector};
+use std::io::Write;
+
+// brotliの圧縮レベルとウィンドウサイズ (配信時によく使われる値)
+const BROTLI_QUALITY: u32 = 11;
+const BROTLI_WINDOW: u32 = 22;
+// これより小さいファイルは圧縮の効果が薄い
+pub const MIN_COMPRESS_BYTES: usize = 1024;
+
+// 1つのファイルの圧縮前後のサイズ
+#[derive(Debug, Clone, Default)]
+pub struct CompressionSizes {
+    pub name: String,
+    pub raw: usize,
+    pub gzip: usize,
+    pub brotli: usize,
+}
+
+impl CompressionSizes {
+    pub fn measure(name: &str, data: &[u8]) -> Self {
+        CompressionSizes {
+            name: name.to_string(),
+            raw: data.len(),
+            gzip: gzip_size(data),
+            brotli: brotli_size(data),
+        }
+    }
+
+    // 配信されているエンコーディングでの転送量
+    pub fn served(&self, encoding: Option<&str>) -> usize {
+        match encoding {
+            Some("br") => self.brotli,
+            // zstdのサイズは測っていないので、圧縮率の近いbrotliで近似する
+            Some("zstd") => self.brotli,
+            Some("gzip") | Some("x-gzip") | Some("deflate") => self.gzip,
+            _ => self.raw,
+        }
+    }
+
+    // 最も小さくなるエンコーディングに変えた場合に減らせるバイト数
+    pub fn savings(&self, encoding: Option<&str>) -> usize {
+        if self.raw < MIN_COMPRESS_BYTES {
+            return 0;
+        }
+        self.served(encoding)
+            .saturating_sub(self.gzip.min(self.brotli))
+    }
+}
+
+// ページ全体の圧縮の結果
+#[derive(Debug, Clone, Default)]
+pub struct CompressionReport {
+    pub html: CompressionSizes,
+    pub resources: Vec<CompressionSizes>, // 保存済みのCSS/JS
+    pub content_encoding: Option<String>, // 取得時のContent-Encoding
+    pub potential_savings: usize,
+}
+
+impl CompressionReport {
+    pub fn total_raw(&self) -> usize {
+        self.html.raw + self.resources.iter().map(|r| r.raw).sum::<usize>()
+    }
+
+    // ヘッダを保存していない場合は判定できないのでNone
+    pub fn is_compressed(&self) -> Option<bool> {
+        let encoding = self.content_encoding.as_deref()?;
+        Some(matches!(
+            encoding,
+            "br" | "gzip" | "x-gzip" | "deflate" | "zstd"
+        ))
+    }
+}
+
+pub fn gzip_size(data: &[u8]) -> usize {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).expect("圧縮に失敗しました");
+    encoder.finish().expect("圧縮に失敗しました").len()
+}
+
+pub fn brotli_size(data: &[u8]) -> usize {
+    let mut encoder =
+        brotli::CompressorWriter::new(Vec::new(), 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+    encoder.write_all(data).expect("圧縮に失敗しました");
+    encoder.into_inner().len()
+}
+
+// gzip後のサイズと元のサイズの比 (小さいほど繰り返しの多い内容)
+pub fn compression_ratio(text: &str) -> f64 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    gzip_size(text.as_bytes()) as f64 / text.len() as f64
+}
+
+pub fn analyze_html(html_content: &str, html_path: &str) -> CompressionReport {
+    let document = Html::parse_document(html_content);
+    let selector = Selector::parse(r#"link[rel~="stylesheet"][href], script[src]"#).unwrap();
+
+    let mut resources = Vec::new();
+    for element in document.select(&selector) {
+        let value = element.value();
+        let url = value.attr("href").or(value.attr("src")).unwrap_or("");
+        let Some(content) =
+            images::local_path(html_path, url).and_then(|path| std::fs::read(path).ok())
+        else {
+            continue;
+        };
+        resources.push(CompressionSizes::measure(url, &content));
+    }
+
+    let content_encoding = headers::load_headers(html_path)
+        .get("content-encoding")
+        .map(|encoding| encoding.trim().to_lowercase());
+    let html = CompressionSizes::measure(html_path, html_content.as_bytes());
+    // 保存済みのCSS/JSは同じサーバーから同じエンコーディングで配信されるものとみなす
+    let potential_savings = std::iter::once(&html)
+        .chain(&resources)
+        .map(|sizes| sizes.savings(content_encoding.as_deref()))
+        .sum();
+
+    CompressionReport {
+        html,
+        resources,
+        content_encoding,
+        potential_savings,
+    }
+}
+
+pub fn analyze(file_path: &str) -> CompressionReport {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    analyze_html(&html_content, file_path)
+}
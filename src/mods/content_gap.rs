@@ -0,0 +1,321 @@
+use crate::mods::cooccurrence;
+use crate::mods::corpus::{self, Page, Scope};
+use crate::mods::corpus_stats::query_terms;
+use crate::mods::dom_counter;
+use crate::mods::main_content;
+use crate::mods::statistics;
+use crate::mods::structured_data;
+use crate::mods::tokenaize;
+use std::collections::{HashMap, HashSet};
+
+// 競合の何割以上が扱っていれば不足とみなすか
+pub const MIN_PREVALENCE: f64 = 0.5;
+// 競合の中央値に対してこの割合未満の使用回数なら少ないとみなす
+const UNDERUSE_RATIO: f64 = 0.5;
+// FAQとみなす見出しの語
+const FAQ_WORDS: [&str; 5] = ["よくある質問", "よくあるご質問", "faq", "q&a", "質問"];
+
+// 競合の見出しにあって対象ページの見出しにない話題
+#[derive(Debug, Clone, Default)]
+pub struct HeadingGap {
+    pub topic: String,
+    pub prevalence: f64,       // 見出しでこの語を扱う競合の割合
+    pub examples: Vec<String>, // 競合の見出しの例
+}
+
+// 対象ページで使用が少ない語
+#[derive(Debug, Clone, Default)]
+pub struct TermGap {
+    pub term: String,
+    pub prevalence: f64,
+    pub target_count: usize,
+    pub competitor_median: f64,
+    pub cooccurrence: bool, // クエリとの共起語か
+}
+
+// 対象ページにない要素 (画像・表・FAQ・構造化データなど)
+#[derive(Debug, Clone, Default)]
+pub struct ElementGap {
+    pub name: String,
+    pub prevalence: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContentGapReport {
+    pub target: String,
+    pub competitors: usize,
+    pub headings: Vec<HeadingGap>,
+    pub terms: Vec<TermGap>,
+    pub elements: Vec<ElementGap>,
+    pub structured_data: Vec<ElementGap>,
+}
+
+// 本文の見出し (h2以下) と、見出しに含まれる名詞
+fn heading_topics(file_path: &str) -> Vec<(String, HashSet<String>)> {
+    main_content::extract_main(file_path)
+        .headings()
+        .into_iter()
+        .filter(|(level, _)| *level >= 2)
+        .map(|(_, text)| {
+            let topics = tokenaize::tokenize_word(&text)
+                .into_iter()
+                .map(|token| token.to_lowercase())
+                .collect();
+            (text, topics)
+        })
+        .collect()
+}
+
+fn heading_gaps(
+    target: &Page,
+    competitors: &[Page],
+    excluded: &HashSet<String>,
+) -> Vec<HeadingGap> {
+    let target_topics: HashSet<String> = heading_topics(&target.path)
+        .into_iter()
+        .flat_map(|(_, topics)| topics)
+        .collect();
+
+    let mut pages_by_topic: HashMap<String, usize> = HashMap::new();
+    let mut examples: HashMap<String, Vec<String>> = HashMap::new();
+    for competitor in competitors {
+        let headings = heading_topics(&competitor.path);
+        let mut seen = HashSet::new();
+        for (text, topics) in &headings {
+            for topic in topics {
+                if seen.insert(topic.clone()) {
+                    *pages_by_topic.entry(topic.clone()).or_insert(0) += 1;
+                    examples
+                        .entry(topic.clone())
+                        .or_default()
+                        .push(text.clone());
+                }
+            }
+        }
+    }
+
+    let total = competitors.len().max(1) as f64;
+    let mut gaps: Vec<HeadingGap> = pages_by_topic
+        .into_iter()
+        .map(|(topic, count)| (topic, count as f64 / total))
+        .filter(|(topic, prevalence)| {
+            *prevalence >= MIN_PREVALENCE
+                && !target_topics.contains(topic)
+                && !excluded.contains(topic)
+        })
+        .map(|(topic, prevalence)| HeadingGap {
+            examples: examples.remove(&topic).unwrap_or_default(),
+            topic,
+            prevalence,
+        })
+        .collect();
+    gaps.sort_by(|a, b| {
+        b.prevalence
+            .total_cmp(&a.prevalence)
+            .then(a.topic.cmp(&b.topic))
+    });
+    gaps
+}
+
+fn term_counts(page: &Page) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for token in page.tokens() {
+        *counts.entry(token).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn term_gaps(
+    target: &Page,
+    competitors: &[Page],
+    main_query: &str,
+    sub_query: &str,
+    excluded: &HashSet<String>,
+) -> Vec<TermGap> {
+    let target_counts = term_counts(target);
+    let competitor_counts: Vec<HashMap<String, usize>> =
+        competitors.iter().map(term_counts).collect();
+    let mut gaps: Vec<TermGap> = cooccurrence::analyze(competitors, main_query, sub_query)
+        .into_iter()
+        // excludedは見出しの語と比べるため小文字にしてある
+        .filter(|term| {
+            term.df_ratio >= MIN_PREVALENCE && !excluded.contains(&term.term.to_lowercase())
+        })
+        .filter_map(|term| {
+            let counts: Vec<f64> = competitor_counts
+                .iter()
+                .map(|counts| counts.get(&term.term).copied().unwrap_or(0) as f64)
+                .collect();
+            let competitor_median = statistics::median(&counts);
+            let target_count = target_counts.get(&term.term).copied().unwrap_or(0);
+            ((target_count as f64) < competitor_median * UNDERUSE_RATIO || target_count == 0)
+                .then_some(TermGap {
+                    prevalence: term.df_ratio,
+                    target_count,
+                    competitor_median,
+                    // クエリと同じ要素に有意に多く出現している
+                    cooccurrence: term.main.is_significant() || term.sub.is_significant(),
+                    term: term.term,
+                })
+        })
+        .collect();
+    gaps.sort_by(|a, b| {
+        b.prevalence
+            .total_cmp(&a.prevalence)
+            .then(b.competitor_median.total_cmp(&a.competitor_median))
+    });
+    gaps
+}
+
+// ページが持っている要素の名前
+fn page_elements(file_path: &str) -> HashSet<&'static str> {
+    let counts = dom_counter::count_tags(file_path);
+    let has = |tags: &[&str]| {
+        tags.iter()
+            .any(|tag| counts.get(*tag).is_some_and(|c| *c > 0))
+    };
+    let has_faq = structured_data::extract(file_path).has_type("FAQPage")
+        || heading_topics(file_path).iter().any(|(text, _)| {
+            let text = text.to_lowercase();
+            FAQ_WORDS.iter().any(|word| text.contains(word))
+        });
+    [
+        ("画像", has(&["img", "picture"])),
+        ("動画", has(&["video", "iframe"])),
+        ("表", has(&["table"])),
+        ("箇条書き", has(&["ul", "ol"])),
+        ("定義リスト", has(&["dl"])),
+        ("引用", has(&["blockquote"])),
+        ("FAQ", has_faq),
+    ]
+    .into_iter()
+    .filter(|(_, present)| *present)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+// 競合のうち指定の名前を持つページの割合で不足を求める
+fn prevalence_gaps<T: Eq + std::hash::Hash + ToString>(
+    target: &HashSet<T>,
+    competitors: &[HashSet<T>],
+) -> Vec<ElementGap> {
+    let mut counts: HashMap<&T, usize> = HashMap::new();
+    for names in competitors {
+        for name in names {
+            *counts.entry(name).or_insert(0) += 1;
+        }
+    }
+    let total = competitors.len().max(1) as f64;
+    let mut gaps: Vec<ElementGap> = counts
+        .into_iter()
+        .filter(|(name, _)| !target.contains(*name))
+        .map(|(name, count)| ElementGap {
+            name: name.to_string(),
+            prevalence: count as f64 / total,
+        })
+        .filter(|gap| gap.prevalence >= MIN_PREVALENCE)
+        .collect();
+    gaps.sort_by(|a, b| {
+        b.prevalence
+            .total_cmp(&a.prevalence)
+            .then(a.name.cmp(&b.name))
+    });
+    gaps
+}
+
+fn structured_types(file_path: &str) -> HashSet<String> {
+    structured_data::extract(file_path)
+        .types()
+        .into_iter()
+        .collect()
+}
+
+pub fn analyze(
+    target_path: &str,
+    competitors: &[Page],
+    main_query: &str,
+    sub_query: &str,
+) -> ContentGapReport {
+    let target = corpus::load_page_in(0, target_path, Scope::MainContent);
+    let excluded: HashSet<String> = query_terms(main_query)
+        .into_iter()
+        .chain(query_terms(sub_query))
+        .map(|term| term.to_lowercase())
+        .collect();
+
+    let competitor_elements: Vec<HashSet<&str>> = competitors
+        .iter()
+        .map(|page| page_elements(&page.path))
+        .collect();
+    let competitor_types: Vec<HashSet<String>> = competitors
+        .iter()
+        .map(|page| structured_types(&page.path))
+        .collect();
+
+    ContentGapReport {
+        target: target_path.to_string(),
+        competitors: competitors.len(),
+        headings: heading_gaps(&target, competitors, &excluded),
+        terms: term_gaps(&target, competitors, main_query, sub_query, &excluded),
+        elements: prevalence_gaps(&page_elements(target_path), &competitor_elements),
+        structured_data: prevalence_gaps(&structured_types(target_path), &competitor_types),
+    }
+}
+
+// 保存済みの上位10ページと比べる
+pub fn analyze_against_top(
+    target_path: &str,
+    main_query: &str,
+    sub_query: &str,
+) -> ContentGapReport {
+    let competitors = corpus::load_pages_in(corpus::TOP_RANKS, Scope::MainContent);
+    analyze(target_path, &competitors, main_query, sub_query)
+}
+
+fn percent(ratio: f64) -> String {
+    format!("{:.0}%", ratio * 100.0)
+}
+
+impl ContentGapReport {
+    // 対応すべき項目をMarkdownで書き出す
+    pub fn to_markdown(&self) -> String {
+        let mut result = format!(
+            "# コンテンツギャップ: {}\n\n上位{}ページとの比較\n",
+            self.target, self.competitors
+        );
+
+        result.push_str("\n## 不足している見出しの話題\n\n");
+        for gap in &self.headings {
+            let examples: Vec<&str> = gap.examples.iter().take(3).map(String::as_str).collect();
+            result.push_str(&format!(
+                "- {} ({}) 例: {}\n",
+                gap.topic,
+                percent(gap.prevalence),
+                examples.join(" / ")
+            ));
+        }
+
+        result.push_str("\n## 使用が少ない語\n\n");
+        for gap in &self.terms {
+            result.push_str(&format!(
+                "- {}{} ({}) 対象: {}回 / 競合の中央値: {}回\n",
+                gap.term,
+                if gap.cooccurrence { " [共起語]" } else { "" },
+                percent(gap.prevalence),
+                gap.target_count,
+                gap.competitor_median
+            ));
+        }
+
+        result.push_str("\n## 不足している要素\n\n");
+        for gap in &self.elements {
+            result.push_str(&format!("- {} ({})\n", gap.name, percent(gap.prevalence)));
+        }
+
+        result.push_str("\n## 不足している構造化データ\n\n");
+        for gap in &self.structured_data {
+            result.push_str(&format!("- {} ({})\n", gap.name, percent(gap.prevalence)));
+        }
+        result
+    }
+}
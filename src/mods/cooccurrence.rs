@@ -0,0 +1,170 @@
+use crate::mods::corpus::Page;
+use crate::mods::statistics;
+use crate::mods::strfn;
+use std::collections::HashSet;
+
+// 自由度1のカイ二乗分布でp < 0.05となるG2の値
+pub const LLR_CRITICAL: f64 = 3.84;
+
+// クエリとの共起の強さ
+#[derive(Debug, Clone, Default)]
+pub struct Cooccurrence {
+    pub count: usize, // 語とクエリが同じ要素に出現した回数
+    pub pmi: f64,
+    pub dice: f64,
+    pub llr: f64, // 対数尤度比 (G2)
+}
+
+impl Cooccurrence {
+    // G2は向きを持たないので、PMIが正 (クエリと一緒に出やすい) かも確かめる
+    pub fn is_significant(&self) -> bool {
+        self.pmi > 0.0 && self.llr >= LLR_CRITICAL
+    }
+}
+
+// 共起語の集計結果
+#[derive(Debug, Clone, Default)]
+pub struct CooccurrenceTerm {
+    pub term: String,
+    pub df: usize,     // 語を含むページ数
+    pub df_ratio: f64, // 語を含むページの割合
+    pub main: Cooccurrence,
+    pub sub: Cooccurrence,
+}
+
+// ページごとに重複を除いたトークンから文書頻度を求める
+pub fn document_frequency(pages: &[Page]) -> Vec<(String, usize)> {
+    let mut unique_tokens = Vec::new();
+    for page in pages {
+        let seen: HashSet<String> = page.tokens().into_iter().collect();
+        unique_tokens.extend(seen);
+    }
+    statistics::word_distribution(unique_tokens)
+}
+
+// 要素ごとにクエリを含むかを判定する
+fn query_flags(pages: &[Page], query: &str) -> Vec<bool> {
+    pages
+        .iter()
+        .flat_map(|page| page.segments.iter())
+        .map(|segment| strfn::contains_word(query, &segment.concat()))
+        .collect()
+}
+
+// 2x2分割表から共起指標を計算する
+fn measure(both: usize, term_total: usize, query_total: usize, total: usize) -> Cooccurrence {
+    let a = both as f64;
+    let b = (term_total - both) as f64;
+    let c = (query_total - both) as f64;
+    let d = total as f64 - a - b - c;
+    let n = total as f64;
+
+    // 共起がない場合は0として扱う
+    let pmi = if both == 0 {
+        0.0
+    } else {
+        (a * n / (term_total as f64 * query_total as f64)).log2()
+    };
+    let dice = if term_total + query_total == 0 {
+        0.0
+    } else {
+        2.0 * a / (term_total + query_total) as f64
+    };
+
+    let cells = [
+        (a, a + b, a + c),
+        (b, a + b, b + d),
+        (c, c + d, a + c),
+        (d, c + d, b + d),
+    ];
+    let llr = 2.0
+        * cells
+            .iter()
+            .filter(|(k, _, _)| *k > 0.0)
+            .map(|(k, row, col)| k * (k * n / (row * col)).ln())
+            .sum::<f64>();
+
+    Cooccurrence {
+        count: both,
+        pmi,
+        dice,
+        llr,
+    }
+}
+
+fn cooccurrence_with(pages: &[Page], flags: &[bool], term: &str) -> Cooccurrence {
+    let segments = pages.iter().flat_map(|page| page.segments.iter());
+    let mut both = 0;
+    let mut term_total = 0;
+    for (segment, &has_query) in segments.zip(flags) {
+        if segment.iter().any(|token| token == term) {
+            term_total += 1;
+            if has_query {
+                both += 1;
+            }
+        }
+    }
+    let query_total = flags.iter().filter(|&&flag| flag).count();
+    measure(both, term_total, query_total, flags.len())
+}
+
+// 上位ページ群の共起語を文書頻度の高い順に返す
+pub fn analyze(pages: &[Page], main_query: &str, sub_query: &str) -> Vec<CooccurrenceTerm> {
+    let main_flags = query_flags(pages, main_query);
+    let sub_flags = query_flags(pages, sub_query);
+    let page_count = pages.len().max(1) as f64;
+
+    let mut result: Vec<CooccurrenceTerm> = document_frequency(pages)
+        .into_iter()
+        .filter(|(term, _)| term != main_query && term != sub_query)
+        .map(|(term, df)| CooccurrenceTerm {
+            main: cooccurrence_with(pages, &main_flags, &term),
+            sub: cooccurrence_with(pages, &sub_flags, &term),
+            df_ratio: df as f64 / page_count,
+            df,
+            term,
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        b.df.cmp(&a.df)
+            .then((b.main.llr + b.sub.llr).total_cmp(&(a.main.llr + a.sub.llr)))
+    });
+    result
+}
+
+// 上位ページの多くが使っているのに対象ページにない語を返す
+pub fn missing_terms(
+    terms: &[CooccurrenceTerm],
+    target: &Page,
+    min_ratio: f64,
+) -> Vec<CooccurrenceTerm> {
+    let target_tokens: HashSet<String> = target.tokens().into_iter().collect();
+    terms
+        .iter()
+        .filter(|term| term.df_ratio >= min_ratio && !target_tokens.contains(&term.term))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a=10, b=10, c=10, d=70 の分割表を手計算した値
+    #[test]
+    fn measure_known_table() {
+        let result = measure(10, 20, 20, 100);
+        assert_eq!(result.count, 10);
+        assert!((result.pmi - 2.5_f64.log2()).abs() < 1e-9);
+        assert!((result.dice - 0.5).abs() < 1e-9);
+        assert!((result.llr - 12.071371684).abs() < 1e-6);
+    }
+
+    // 独立な場合はPMIもG2も0になる
+    #[test]
+    fn measure_independent_table() {
+        let result = measure(4, 20, 20, 100);
+        assert!(result.pmi.abs() < 1e-9);
+        assert!(result.llr.abs() < 1e-9);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::mods::extract;
+use crate::mods::main_content;
+use crate::mods::strfn;
+use crate::mods::tokenaize;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+// 保存済みSERPの置き場所と上位・下位の範囲
+pub const DB_DIR: &str = "./db";
+pub const TOP_RANKS: RangeInclusive<usize> = 0..=9;
+pub const BOTTOM_RANKS: RangeInclusive<usize> = 91..=100;
+
+// 順位帯ごとのサンプルページ
+pub const TEST_DB_DIR: &str = "./test_db";
+pub const TEST_TIERS: [&str; 3] = ["TOP", "MIDDLE", "BOTTOM"];
+
+// テキストとして扱うタグ
+const TEXT_TAGS: [&str; 5] = ["title", "h1", "h2", "h3", "p"];
+
+// ページ全体を使うか、本文だけを使うか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scope {
+    #[default]
+    Whole,
+    MainContent,
+}
+
+// コーパス内の1ページ分のデータ
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    pub rank: usize,
+    pub path: String,
+    pub segments: Vec<Vec<String>>, // 要素ごとのトークン列
+}
+
+impl Page {
+    // ページ全体のトークンを順番通りに返す
+    pub fn tokens(&self) -> Vec<String> {
+        self.segments.iter().flatten().cloned().collect()
+    }
+}
+
+pub fn page_path(rank: usize) -> String {
+    format!("{}/{}.html", DB_DIR, rank)
+}
+
+// 要素ごとにテキストを抜き出す
+pub fn page_texts(file_path: &str) -> Vec<String> {
+    let mut texts = Vec::new();
+    for tag in TEXT_TAGS {
+        let elements = strfn::vec_format(extract::extract_element(tag, file_path));
+        texts.extend(elements);
+    }
+    texts.retain(|text| !text.trim().is_empty());
+    texts
+}
+
+// 範囲を指定してテキストを抜き出す
+pub fn page_texts_in(file_path: &str, scope: Scope) -> Vec<String> {
+    match scope {
+        Scope::Whole => page_texts(file_path),
+        Scope::MainContent => main_content::extract_main(file_path).texts(),
+    }
+}
+
+// 要素ごとにトークン化する
+pub fn page_segments(file_path: &str) -> Vec<Vec<String>> {
+    page_segments_in(file_path, Scope::Whole)
+}
+
+pub fn page_segments_in(file_path: &str, scope: Scope) -> Vec<Vec<String>> {
+    page_texts_in(file_path, scope)
+        .iter()
+        .map(|text| tokenaize::tokenize_word(text))
+        .filter(|tokens| !tokens.is_empty())
+        .collect()
+}
+
+pub fn load_page(rank: usize, file_path: &str) -> Page {
+    load_page_in(rank, file_path, Scope::Whole)
+}
+
+pub fn load_page_in(rank: usize, file_path: &str, scope: Scope) -> Page {
+    Page {
+        rank,
+        path: file_path.to_string(),
+        segments: page_segments_in(file_path, scope),
+    }
+}
+
+pub fn load_pages(ranks: RangeInclusive<usize>) -> Vec<Page> {
+    load_pages_in(ranks, Scope::Whole)
+}
+
+// 存在するファイルだけを読み込む
+pub fn load_pages_in(ranks: RangeInclusive<usize>, scope: Scope) -> Vec<Page> {
+    ranks
+        .filter_map(|rank| {
+            let path = page_path(rank);
+            if Path::new(&path).exists() {
+                Some(load_page_in(rank, &path, scope))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn top_pages() -> Vec<Page> {
+    load_pages(TOP_RANKS)
+}
+
+pub fn bottom_pages() -> Vec<Page> {
+    load_pages(BOTTOM_RANKS)
+}
+
+// test_db内の順位帯サンプルを読み込む
+pub fn tier_page(tier: &str) -> Page {
+    load_page(0, &format!("{}/{}.html", TEST_DB_DIR, tier))
+}
@@ -0,0 +1,140 @@
+use crate::mods::corpus::Page;
+use crate::mods::statistics;
+use crate::mods::tokenaize;
+use std::collections::{HashMap, HashSet};
+
+// BM25のパラメータ
+pub const BM25_K1: f64 = 1.2;
+pub const BM25_B: f64 = 0.75;
+
+// TFの重み付け方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TfWeighting {
+    Raw,       // 出現回数そのまま
+    Log,       // 1 + ln(tf)
+    Augmented, // 0.5 + 0.5 * tf / max_tf
+    Boolean,   // 出現すれば1
+    Relative,  // tf / 文書長
+}
+
+// コーパス全体の統計量
+#[derive(Debug, Clone, Default)]
+pub struct CorpusStats {
+    pub doc_count: usize,
+    pub avg_len: f64,
+    pub df: HashMap<String, usize>,
+}
+
+impl CorpusStats {
+    pub fn from_pages(pages: &[Page]) -> Self {
+        let mut df = HashMap::new();
+        let mut total_len = 0;
+        for page in pages {
+            let tokens = page.tokens();
+            total_len += tokens.len();
+            let seen: HashSet<String> = tokens.into_iter().collect();
+            for term in seen {
+                *df.entry(term).or_insert(0) += 1;
+            }
+        }
+        CorpusStats {
+            doc_count: pages.len(),
+            avg_len: total_len as f64 / pages.len().max(1) as f64,
+            df,
+        }
+    }
+
+    pub fn document_frequency(&self, term: &str) -> usize {
+        self.df.get(term).copied().unwrap_or(0)
+    }
+
+    // 平滑化したIDF
+    pub fn idf(&self, term: &str) -> f64 {
+        let n = self.doc_count as f64;
+        let df = self.document_frequency(term) as f64;
+        ((1.0 + n) / (1.0 + df)).ln() + 1.0
+    }
+
+    // BM25用のIDF
+    pub fn bm25_idf(&self, term: &str) -> f64 {
+        let n = self.doc_count as f64;
+        let df = self.document_frequency(term) as f64;
+        ((n - df + 0.5) / (df + 0.5) + 1.0).ln()
+    }
+
+    // ページ内の各語のTF-IDFを高い順に返す
+    pub fn tf_idf(&self, page: &Page, weighting: TfWeighting) -> Vec<(String, f64)> {
+        let counts = statistics::word_distribution(page.tokens());
+        let max_tf = counts.first().map(|(_, count)| *count).unwrap_or(1) as f64;
+        let doc_len = counts.iter().map(|(_, count)| count).sum::<usize>().max(1) as f64;
+
+        let mut result: Vec<(String, f64)> = counts
+            .into_iter()
+            .map(|(term, count)| {
+                let tf = count as f64;
+                let weight = match weighting {
+                    TfWeighting::Raw => tf,
+                    TfWeighting::Log => 1.0 + tf.ln(),
+                    TfWeighting::Augmented => 0.5 + 0.5 * tf / max_tf,
+                    TfWeighting::Boolean => 1.0,
+                    TfWeighting::Relative => tf / doc_len,
+                };
+                let score = weight * self.idf(&term);
+                (term, score)
+            })
+            .collect();
+        result.sort_by(|a, b| b.1.total_cmp(&a.1));
+        result
+    }
+
+    // クエリに対するページのBM25スコア
+    pub fn bm25(&self, page: &Page, query: &str) -> f64 {
+        let tokens = page.tokens();
+        let doc_len = tokens.len() as f64;
+        let avg_len = if self.avg_len > 0.0 {
+            self.avg_len
+        } else {
+            1.0
+        };
+        let counts: HashMap<String, usize> =
+            statistics::word_distribution(tokens).into_iter().collect();
+
+        query_terms(query)
+            .iter()
+            .map(|term| {
+                let tf = counts.get(term).copied().unwrap_or(0) as f64;
+                let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / avg_len);
+                self.bm25_idf(term) * tf * (BM25_K1 + 1.0) / (tf + norm)
+            })
+            .sum()
+    }
+
+    // 第一・第二クエリそれぞれと、両方を合わせたBM25スコア
+    pub fn bm25_pair(&self, page: &Page, main_query: &str, sub_query: &str) -> (f64, f64, f64) {
+        let main = self.bm25(page, main_query);
+        let sub = self.bm25(page, sub_query);
+        (main, sub, main + sub)
+    }
+
+    // ページを特徴づける語を上位n件返す
+    pub fn distinctive_terms(
+        &self,
+        page: &Page,
+        weighting: TfWeighting,
+        n: usize,
+    ) -> Vec<(String, f64)> {
+        let mut terms = self.tf_idf(page, weighting);
+        terms.truncate(n);
+        terms
+    }
+}
+
+// クエリをページと同じ方法でトークン化する
+pub fn query_terms(query: &str) -> Vec<String> {
+    let terms = tokenaize::tokenize_word(query);
+    if terms.is_empty() {
+        vec![query.to_string()]
+    } else {
+        terms
+    }
+}
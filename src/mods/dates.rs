@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use regex::Regex;
+
+// 和暦の元年 (西暦)
+const ERAS: [(&str, i32); 3] = [("令和", 2019), ("平成", 1989), ("昭和", 1926)];
+// 日付として扱う年の範囲
+const MIN_YEAR: i32 = 1970;
+const MAX_YEAR: i32 = 2100;
+
+// テキスト中で見つけた日付
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundDate {
+    pub date: NaiveDate,
+    pub text: String,
+    pub start: usize, // 全角数字を半角にした後のバイト位置
+}
+
+// 全角数字を半角にする
+pub fn normalize_digits(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '／' => '/',
+            '－' => '-',
+            _ => c,
+        })
+        .collect()
+}
+
+fn era_year(era: &str, year: &str) -> Option<i32> {
+    let base = ERAS.iter().find(|(name, _)| *name == era)?.1;
+    let year: i32 = if year == "元" { 1 } else { year.parse().ok()? };
+    Some(base + year - 1)
+}
+
+fn make_date(year: i32, month: &str, day: &str) -> Option<NaiveDate> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+// 2024年10月17日 / 令和6年10月17日 / 2024-10-17 / 2024/10/17 / 2024.10.17 を探す
+pub fn find_dates(text: &str) -> Vec<FoundDate> {
+    let text = normalize_digits(text);
+    let western = Regex::new(r"(\d{4})\s*年\s*(\d{1,2})\s*月\s*(\d{1,2})\s*日").unwrap();
+    let japanese =
+        Regex::new(r"(令和|平成|昭和)\s*(元|\d{1,2})\s*年\s*(\d{1,2})\s*月\s*(\d{1,2})\s*日")
+            .unwrap();
+    let numeric = Regex::new(r"(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})").unwrap();
+
+    let mut dates = Vec::new();
+    for captures in western.captures_iter(&text) {
+        let year = captures[1].parse().unwrap_or(0);
+        if let Some(date) = make_date(year, &captures[2], &captures[3]) {
+            let matched = captures.get(0).unwrap();
+            dates.push(FoundDate {
+                date,
+                text: matched.as_str().to_string(),
+                start: matched.start(),
+            });
+        }
+    }
+    for captures in japanese.captures_iter(&text) {
+        let Some(year) = era_year(&captures[1], &captures[2]) else {
+            continue;
+        };
+        if let Some(date) = make_date(year, &captures[3], &captures[4]) {
+            let matched = captures.get(0).unwrap();
+            dates.push(FoundDate {
+                date,
+                text: matched.as_str().to_string(),
+                start: matched.start(),
+            });
+        }
+    }
+    for captures in numeric.captures_iter(&text) {
+        let matched = captures.get(0).unwrap();
+        // 電話番号や長い数字の一部は除く
+        let digit_before = text[..matched.start()].ends_with(|c: char| c.is_ascii_digit());
+        let digit_after = text[matched.end()..].starts_with(|c: char| c.is_ascii_digit());
+        if digit_before || digit_after {
+            continue;
+        }
+        let year = captures[1].parse().unwrap_or(0);
+        if let Some(date) = make_date(year, &captures[2], &captures[3]) {
+            dates.push(FoundDate {
+                date,
+                text: matched.as_str().to_string(),
+                start: matched.start(),
+            });
+        }
+    }
+    dates.sort_by_key(|found| found.start);
+    dates
+}
+
+// 属性値などの日付を1つ読み取る (ISO 8601の日時にも対応)
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.date_naive());
+    }
+    if let Ok(datetime) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        return Some(datetime.date());
+    }
+    find_dates(value).into_iter().next().map(|found| found.date)
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+// 全要素をタグ名ごとに数える
+pub fn count_tags_html(html_content: &str) -> HashMap<String, i32> {
+    let document = scraper::Html::parse_document(html_content);
+
+    let mut counts = HashMap::new();
+    for node in document.tree.nodes() {
+        if let Some(element) = scraper::ElementRef::wrap(node) {
+            *counts
+                .entry(element.value().name().to_string())
+                .or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+pub fn count_tags(file_path: &str) -> HashMap<String, i32> {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    count_tags_html(&html_content)
+}
+
+// DOMの要素数
+pub fn dom_size(file_path: &str) -> i32 {
+    count_tags(file_path).values().sum()
+}
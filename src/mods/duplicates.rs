@@ -0,0 +1,247 @@
+use crate::mods::corpus::{self, Page, Scope};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+// 重複検出の設定
+#[derive(Debug, Clone)]
+pub struct DuplicateConfig {
+    pub shingle_size: usize, // 何語ずつ区切るか
+    pub num_hashes: usize,   // MinHashの署名の長さ (bands * rows)
+    pub bands: usize,
+    pub threshold: f64,   // 重複とみなす推定Jaccard係数
+    pub max_hamming: u32, // 重複とみなすSimHashのハミング距離
+}
+
+impl Default for DuplicateConfig {
+    fn default() -> Self {
+        DuplicateConfig {
+            shingle_size: 3,
+            num_hashes: 128,
+            bands: 32,
+            threshold: 0.5,
+            max_hamming: 3,
+        }
+    }
+}
+
+// ページ1つ分の指紋
+#[derive(Debug, Clone, Default)]
+pub struct Fingerprint {
+    pub rank: usize,
+    pub path: String,
+    pub shingles: usize,
+    pub simhash: u64,
+    pub minhash: Vec<u64>,
+}
+
+// 重複と判定したページの組
+#[derive(Debug, Clone)]
+pub struct DuplicatePair {
+    pub a: usize, // 順位
+    pub b: usize,
+    pub jaccard: f64, // MinHashから推定したJaccard係数
+    pub hamming: u32,
+}
+
+fn hash_with<T: Hash + ?Sized>(seed: u64, value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// 記号だけのトークンを除き、小文字にそろえる
+fn normalize_tokens(tokens: &[String]) -> Vec<String> {
+    tokens
+        .iter()
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+// 連続するsize語をまとめたシングルのハッシュ (出現回数つき)
+pub fn shingles(tokens: &[String], size: usize) -> HashMap<u64, usize> {
+    let tokens = normalize_tokens(tokens);
+    let mut counts = HashMap::new();
+    if tokens.is_empty() {
+        return counts;
+    }
+    // sizeが0でもwindowsがpanicしないよう1語以上にする
+    for window in tokens.windows(size.clamp(1, tokens.len())) {
+        *counts.entry(hash_with(0, &window)).or_insert(0) += 1;
+    }
+    counts
+}
+
+// 出現回数で重み付けしたSimHash
+pub fn simhash(shingles: &HashMap<u64, usize>) -> u64 {
+    let mut weights = [0i64; 64];
+    for (hash, count) in shingles {
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash >> bit & 1 == 1 {
+                *weight += *count as i64;
+            } else {
+                *weight -= *count as i64;
+            }
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0, |fingerprint, (bit, _)| fingerprint | 1 << bit)
+}
+
+pub fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+// ハッシュ関数ごとの最小値を並べた署名
+pub fn minhash(shingles: &HashMap<u64, usize>, num_hashes: usize) -> Vec<u64> {
+    (0..num_hashes as u64)
+        .map(|seed| {
+            shingles
+                .keys()
+                .map(|shingle| hash_with(seed + 1, shingle))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+// 署名の一致率でJaccard係数を推定する
+pub fn estimated_jaccard(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / a.len() as f64
+}
+
+pub fn fingerprint(page: &Page, config: &DuplicateConfig) -> Fingerprint {
+    let shingles = shingles(&page.tokens(), config.shingle_size);
+    Fingerprint {
+        rank: page.rank,
+        path: page.path.clone(),
+        shingles: shingles.len(),
+        simhash: simhash(&shingles),
+        minhash: minhash(&shingles, config.num_hashes),
+    }
+}
+
+// LSHのバンドが1つでも一致した組を候補にする
+pub fn candidate_pairs(
+    fingerprints: &[Fingerprint],
+    config: &DuplicateConfig,
+) -> Vec<(usize, usize)> {
+    let rows = (config.num_hashes / config.bands).max(1);
+    let mut candidates = HashSet::new();
+    for band in 0..config.bands {
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (index, fingerprint) in fingerprints.iter().enumerate() {
+            if fingerprint.shingles == 0 {
+                continue;
+            }
+            let start = (band * rows).min(fingerprint.minhash.len());
+            let end = (start + rows).min(fingerprint.minhash.len());
+            let key = hash_with(band as u64, &fingerprint.minhash[start..end]);
+            buckets.entry(key).or_default().push(index);
+        }
+        for members in buckets.values() {
+            for (i, &a) in members.iter().enumerate() {
+                for &b in &members[i + 1..] {
+                    candidates.insert((a, b));
+                }
+            }
+        }
+    }
+    let mut candidates: Vec<(usize, usize)> = candidates.into_iter().collect();
+    candidates.sort();
+    candidates
+}
+
+// 候補の組を推定Jaccard係数とSimHashで確かめる
+pub fn near_duplicates(
+    fingerprints: &[Fingerprint],
+    config: &DuplicateConfig,
+) -> Vec<DuplicatePair> {
+    candidate_pairs(fingerprints, config)
+        .into_iter()
+        .filter_map(|(a, b)| {
+            let (a, b) = (&fingerprints[a], &fingerprints[b]);
+            let jaccard = estimated_jaccard(&a.minhash, &b.minhash);
+            let hamming = hamming(a.simhash, b.simhash);
+            (jaccard >= config.threshold || hamming <= config.max_hamming).then_some(
+                DuplicatePair {
+                    a: a.rank,
+                    b: b.rank,
+                    jaccard,
+                    hamming,
+                },
+            )
+        })
+        .collect()
+}
+
+// Union-Findの根を探す
+fn find(parent: &mut HashMap<usize, usize>, x: usize) -> usize {
+    let p = *parent.entry(x).or_insert(x);
+    if p == x {
+        return x;
+    }
+    let root = find(parent, p);
+    parent.insert(x, root);
+    root
+}
+
+// 重複の組をつないだクラスタ (2ページ以上のものだけ)
+pub fn clusters(pairs: &[DuplicatePair]) -> Vec<Vec<usize>> {
+    let mut parent: HashMap<usize, usize> = HashMap::new();
+    for pair in pairs {
+        let (a, b) = (find(&mut parent, pair.a), find(&mut parent, pair.b));
+        if a != b {
+            parent.insert(a.max(b), a.min(b));
+        }
+    }
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    let members: Vec<usize> = parent.keys().copied().collect();
+    for member in members {
+        let root = find(&mut parent, member);
+        groups.entry(root).or_default().push(member);
+    }
+    let mut clusters: Vec<Vec<usize>> = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort();
+            group
+        })
+        .collect();
+    clusters.sort();
+    clusters
+}
+
+// 競合ページとの最大の類似度を1から引いた独自性 (0～1)
+pub fn originality(page: &Page, competitors: &[Page], config: &DuplicateConfig) -> f64 {
+    let target = fingerprint(page, config);
+    if target.shingles == 0 {
+        return 0.0;
+    }
+    let max_similarity = competitors
+        .iter()
+        .filter(|competitor| competitor.path != page.path)
+        .map(|competitor| {
+            estimated_jaccard(&target.minhash, &fingerprint(competitor, config).minhash)
+        })
+        .fold(0.0, f64::max);
+    1.0 - max_similarity
+}
+
+// コーパス全体の本文から重複クラスタを求める
+pub fn corpus_clusters(config: &DuplicateConfig) -> Vec<Vec<usize>> {
+    let pages = corpus::load_pages_in(0..=*corpus::BOTTOM_RANKS.end(), Scope::MainContent);
+    let fingerprints: Vec<Fingerprint> =
+        pages.iter().map(|page| fingerprint(page, config)).collect();
+    clusters(&near_duplicates(&fingerprints, config))
+}
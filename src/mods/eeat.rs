@@ -0,0 +1,289 @@
+use crate::mods::dates;
+use crate::mods::main_content;
+use crate::mods::metadata;
+use crate::mods::structured_data::{self, PropValue, StructuredData};
+use crate::mods::traits::Formatter;
+use chrono::NaiveDate;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+// 著者名の前に置かれる語
+const AUTHOR_LABELS: [&str; 5] = ["著者", "執筆者", "筆者", "執筆", "ライター"];
+const SUPERVISOR_LABELS: [&str; 3] = ["監修者", "記事監修", "監修"];
+// 著者の情報を持つ要素のclass/idに含まれる語
+const AUTHOR_CLASSES: [&str; 4] = ["author", "byline", "writer", "profile"];
+// プロフィールページのURLに含まれる語
+const PROFILE_PATHS: [&str; 5] = ["/author/", "/authors/", "/profile/", "/writer/", "/users/"];
+// 公開日・更新日のラベル
+pub const PUBLISHED_LABELS: [&str; 4] = ["公開日", "投稿日", "掲載日", "作成日"];
+pub const MODIFIED_LABELS: [&str; 3] = ["更新日", "最終更新", "更新"];
+// 運営者の情報へのリンク
+const CONTACT_WORDS: [&str; 4] = ["お問い合わせ", "お問合せ", "問い合わせ", "contact"];
+const ABOUT_WORDS: [&str; 6] = [
+    "会社概要",
+    "運営者",
+    "運営会社",
+    "私たちについて",
+    "about",
+    "company",
+];
+// 信頼できる情報源とみなすドメインの末尾
+const AUTHORITATIVE_SUFFIXES: [&str; 10] = [
+    ".go.jp",
+    ".lg.jp",
+    ".ac.jp",
+    ".ed.jp",
+    ".gov",
+    ".edu",
+    ".int",
+    "wikipedia.org",
+    "e-gov.go.jp",
+    "nih.gov",
+];
+
+// E-E-A-Tに関する情報
+#[derive(Debug, Clone, Default)]
+pub struct EeatSignals {
+    pub authors: Vec<String>,
+    pub supervisors: Vec<String>, // 監修者
+    pub profile_links: Vec<String>,
+    pub published: Option<NaiveDate>,
+    pub modified: Option<NaiveDate>,
+    pub organizations: Vec<String>,
+    pub contact_links: Vec<String>,
+    pub about_links: Vec<String>,
+    pub external_links: usize,
+    pub citations: Vec<String>, // 信頼できるドメインへのリンク
+}
+
+impl EeatSignals {
+    // 見つかった情報の種類の割合 (0～1)
+    pub fn score(&self) -> f64 {
+        let signals = [
+            !self.authors.is_empty(),
+            !self.supervisors.is_empty(),
+            !self.profile_links.is_empty(),
+            self.published.is_some() || self.modified.is_some(),
+            !self.organizations.is_empty(),
+            !self.contact_links.is_empty() || !self.about_links.is_empty(),
+            !self.citations.is_empty(),
+        ];
+        signals.iter().filter(|&&signal| signal).count() as f64 / signals.len() as f64
+    }
+
+    pub fn has_author(&self) -> bool {
+        !self.authors.is_empty() || !self.supervisors.is_empty()
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.replace_ws().format_ws()
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    let value = normalize(&value);
+    if !value.is_empty() && !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+pub fn is_authoritative(host: &str) -> bool {
+    let host = host.to_lowercase();
+    AUTHORITATIVE_SUFFIXES
+        .iter()
+        .any(|suffix| host.ends_with(suffix))
+}
+
+// エンティティのプロパティから名前を取り出す (文字列か、nameを持つエンティティ)
+fn names_of(data: &StructuredData, values: &[PropValue]) -> Vec<String> {
+    values
+        .iter()
+        .filter_map(|value| match value {
+            PropValue::Text(text) => Some(text.clone()),
+            PropValue::Entity(index) => {
+                match data.entities[*index].properties.get("name")?.first()? {
+                    PropValue::Text(name) => Some(name.clone()),
+                    PropValue::Entity(_) => None,
+                }
+            }
+        })
+        .collect()
+}
+
+// "監修：山田太郎" のようなラベル付きの名前
+fn labelled_names(text: &str, labels: &[&str]) -> Vec<String> {
+    let pattern = format!(
+        r"(?:{})\s*[:：]\s*([^\s、,|｜/／]{{2,20}})",
+        labels.join("|")
+    );
+    let label = Regex::new(&pattern).unwrap();
+    label
+        .captures_iter(text)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+// ラベルの直後にある日付
+fn labelled_date(text: &str, labels: &[&str]) -> Option<NaiveDate> {
+    let text = dates::normalize_digits(text);
+    labels.iter().find_map(|label| {
+        let position = text.find(label)?;
+        let rest: String = text[position + label.len()..].chars().take(30).collect();
+        dates::find_dates(&rest)
+            .into_iter()
+            .next()
+            .map(|found| found.date)
+    })
+}
+
+fn has_author_class(element: &ElementRef) -> bool {
+    let value = element.value();
+    let names = format!(
+        "{} {} {}",
+        value.attr("class").unwrap_or(""),
+        value.attr("id").unwrap_or(""),
+        value.attr("itemprop").unwrap_or("")
+    )
+    .to_lowercase();
+    AUTHOR_CLASSES.iter().any(|name| names.contains(name))
+}
+
+pub fn extract_html(html_content: &str) -> EeatSignals {
+    let document = Html::parse_document(html_content);
+    let metadata = metadata::parse_metadata_html(html_content);
+    let data = structured_data::extract_html(html_content);
+    let body_selector = Selector::parse("body").unwrap();
+    let link_selector = Selector::parse("a[href]").unwrap();
+    let body_text = document
+        .select(&body_selector)
+        .next()
+        .map(|body| normalize(&main_content::visible_text(&body)))
+        .unwrap_or_default();
+    let page_host = metadata.page_url().and_then(|url| {
+        url.host_str()
+            .map(|host| host.trim_start_matches("www.").to_string())
+    });
+    let mut signals = EeatSignals::default();
+
+    // 構造化データ
+    for entity in &data.entities {
+        let property = |name: &str| entity.properties.get(name).cloned().unwrap_or_default();
+        for name in names_of(&data, &property("author")) {
+            push_unique(&mut signals.authors, name);
+        }
+        for name in names_of(&data, &property("reviewedBy")) {
+            push_unique(&mut signals.supervisors, name);
+        }
+        for name in names_of(&data, &property("publisher")) {
+            push_unique(&mut signals.organizations, name);
+        }
+        if entity
+            .types
+            .iter()
+            .any(|t| t == "Organization" || t == "Corporation")
+        {
+            for name in names_of(&data, &property("name")) {
+                push_unique(&mut signals.organizations, name);
+            }
+        }
+        if entity.types.iter().any(|t| t == "Person") {
+            for url in names_of(&data, &property("url"))
+                .into_iter()
+                .chain(names_of(&data, &property("sameAs")))
+            {
+                push_unique(&mut signals.profile_links, url);
+            }
+        }
+        let date = |name: &str| {
+            names_of(&data, &property(name))
+                .first()
+                .and_then(|value| dates::parse_date(value))
+        };
+        signals.published = signals.published.or(date("datePublished"));
+        signals.modified = signals.modified.or(date("dateModified"));
+    }
+
+    // metaタグ
+    if let Some(author) = metadata.meta("author") {
+        push_unique(&mut signals.authors, author.to_string());
+    }
+    if let Some(site_name) = &metadata.og.site_name {
+        push_unique(&mut signals.organizations, site_name.clone());
+    }
+    signals.published = signals.published.or(metadata
+        .meta("article:published_time")
+        .and_then(dates::parse_date));
+    signals.modified = signals.modified.or(metadata
+        .meta("article:modified_time")
+        .and_then(dates::parse_date));
+
+    // 本文のラベル
+    for name in labelled_names(&body_text, &SUPERVISOR_LABELS) {
+        push_unique(&mut signals.supervisors, name);
+    }
+    for name in labelled_names(&body_text, &AUTHOR_LABELS) {
+        push_unique(&mut signals.authors, name);
+    }
+    signals.published = signals
+        .published
+        .or(labelled_date(&body_text, &PUBLISHED_LABELS));
+    signals.modified = signals
+        .modified
+        .or(labelled_date(&body_text, &MODIFIED_LABELS));
+    // © 2024 株式会社〇〇 のような著作権表示
+    let copyright =
+        Regex::new(r"(?:©|\(c\)|Copyright)\s*(?:\d{4}(?:\s*-\s*\d{4})?)?\s*([^\s.。|｜]{2,30})")
+            .unwrap();
+    if let Some(captures) = copyright.captures(&body_text) {
+        push_unique(&mut signals.organizations, captures[1].to_string());
+    }
+
+    // リンク
+    for link in document.select(&link_selector) {
+        let href = link.value().attr("href").unwrap_or("").trim().to_string();
+        let text = normalize(&main_content::visible_text(&link)).to_lowercase();
+        let href_lower = href.to_lowercase();
+        let in_byline = link
+            .value()
+            .attr("rel")
+            .is_some_and(|rel| rel.contains("author"))
+            || link
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|ancestor| has_author_class(&ancestor));
+        if in_byline || PROFILE_PATHS.iter().any(|path| href_lower.contains(path)) {
+            push_unique(&mut signals.profile_links, href.clone());
+        }
+        if CONTACT_WORDS
+            .iter()
+            .any(|word| text.contains(word) || href_lower.contains(word))
+        {
+            push_unique(&mut signals.contact_links, href.clone());
+        }
+        if ABOUT_WORDS
+            .iter()
+            .any(|word| text.contains(word) || href_lower.contains(word))
+        {
+            push_unique(&mut signals.about_links, href.clone());
+        }
+        let Some(host) = url::Url::parse(&href).ok().and_then(|url| {
+            url.host_str()
+                .map(|host| host.trim_start_matches("www.").to_string())
+        }) else {
+            continue;
+        };
+        if page_host.as_ref() == Some(&host) {
+            continue;
+        }
+        signals.external_links += 1;
+        if is_authoritative(&host) {
+            push_unique(&mut signals.citations, href);
+        }
+    }
+    signals
+}
+
+pub fn extract(file_path: &str) -> EeatSignals {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    extract_html(&html_content)
+}
@@ -0,0 +1,283 @@
+use crate::mods::corpus::Page;
+use crate::mods::corpus_stats::query_terms;
+use ndarray::{Array1, Array2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+pub const EMBEDDING_PATH: &str = "./data/embedding.txt";
+
+// word2vec (skip-gram + ネガティブサンプリング) の学習設定
+#[derive(Debug, Clone)]
+pub struct EmbeddingConfig {
+    pub dim: usize,
+    pub window: usize,
+    pub negative: usize,
+    pub epochs: usize,
+    pub learning_rate: f32,
+    pub min_count: usize,
+    pub seed: u64,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        EmbeddingConfig {
+            dim: 100,
+            window: 5,
+            negative: 5,
+            epochs: 5,
+            learning_rate: 0.025,
+            min_count: 2,
+            seed: 0,
+        }
+    }
+}
+
+// 単語ベクトルの集合
+#[derive(Debug, Clone)]
+pub struct Embeddings {
+    pub vocab: Vec<String>,
+    pub index: HashMap<String, usize>,
+    pub vectors: Array2<f32>,
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn cosine(a: &Array1<f32>, b: &Array1<f32>) -> f32 {
+    let norm = a.dot(a).sqrt() * b.dot(b).sqrt();
+    if norm == 0.0 {
+        0.0
+    } else {
+        a.dot(b) / norm
+    }
+}
+
+// ネガティブサンプリング用に出現頻度の0.75乗で語を並べたテーブル
+fn unigram_table(counts: &[usize], size: usize) -> Vec<usize> {
+    let weights: Vec<f64> = counts.iter().map(|&c| (c as f64).powf(0.75)).collect();
+    let total: f64 = weights.iter().sum();
+    let mut table = Vec::with_capacity(size);
+    for (w, weight) in weights.iter().enumerate() {
+        let slots = (weight / total * size as f64).round() as usize;
+        table.extend(std::iter::repeat_n(w, slots.max(1)));
+    }
+    table
+}
+
+// コーパスの要素単位のトークン列で学習する
+pub fn train(pages: &[Page], config: EmbeddingConfig) -> Embeddings {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for page in pages {
+        for token in page.tokens() {
+            *counts.entry(token).or_insert(0) += 1;
+        }
+    }
+    let mut vocab: Vec<String> = counts
+        .iter()
+        .filter(|(_, &count)| count >= config.min_count)
+        .map(|(word, _)| word.clone())
+        .collect();
+    vocab.sort();
+    let index: HashMap<String, usize> = vocab
+        .iter()
+        .enumerate()
+        .map(|(i, word)| (word.clone(), i))
+        .collect();
+    let word_counts: Vec<usize> = vocab.iter().map(|word| counts[word]).collect();
+
+    let sentences: Vec<Vec<usize>> = pages
+        .iter()
+        .flat_map(|page| page.segments.iter())
+        .map(|segment| {
+            segment
+                .iter()
+                .filter_map(|token| index.get(token).copied())
+                .collect::<Vec<usize>>()
+        })
+        .filter(|sentence| sentence.len() > 1)
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let dim = config.dim;
+    let mut input = Array2::<f32>::zeros((vocab.len(), dim));
+    input.mapv_inplace(|_| (rng.gen::<f32>() - 0.5) / dim as f32);
+    let mut output = Array2::<f32>::zeros((vocab.len(), dim));
+    if vocab.is_empty() {
+        return Embeddings {
+            vocab,
+            index,
+            vectors: input,
+        };
+    }
+    let table = unigram_table(&word_counts, 100_000);
+
+    let total_steps = (config.epochs * sentences.len()).max(1) as f32;
+    let mut step = 0.0;
+    for _ in 0..config.epochs {
+        for sentence in &sentences {
+            // 学習率を線形に減衰させる
+            let lr = (config.learning_rate * (1.0 - step / total_steps))
+                .max(config.learning_rate * 0.0001);
+            step += 1.0;
+            for (i, &center) in sentence.iter().enumerate() {
+                let start = i.saturating_sub(config.window);
+                let end = (i + config.window + 1).min(sentence.len());
+                for (j, &context) in sentence.iter().enumerate().take(end).skip(start) {
+                    if i == j {
+                        continue;
+                    }
+                    let mut grad = Array1::<f32>::zeros(dim);
+                    for n in 0..=config.negative {
+                        let (target, label) = if n == 0 {
+                            (context, 1.0)
+                        } else {
+                            let sample = table[rng.gen_range(0..table.len())];
+                            if sample == context {
+                                continue;
+                            }
+                            (sample, 0.0)
+                        };
+                        let score = sigmoid(input.row(center).dot(&output.row(target)));
+                        let g = (label - score) * lr;
+                        grad.scaled_add(g, &output.row(target));
+                        let center_row = input.row(center).to_owned();
+                        output.row_mut(target).scaled_add(g, &center_row);
+                    }
+                    input.row_mut(center).scaled_add(1.0, &grad);
+                }
+            }
+        }
+    }
+
+    Embeddings {
+        vocab,
+        index,
+        vectors: input,
+    }
+}
+
+impl Embeddings {
+    pub fn vector(&self, word: &str) -> Option<Array1<f32>> {
+        self.index
+            .get(word)
+            .map(|&i| self.vectors.row(i).to_owned())
+    }
+
+    pub fn similarity(&self, a: &str, b: &str) -> Option<f32> {
+        Some(cosine(&self.vector(a)?, &self.vector(b)?))
+    }
+
+    // 類似度の高い語をn件返す
+    pub fn nearest(&self, word: &str, n: usize) -> Vec<(String, f32)> {
+        let Some(target) = self.vector(word) else {
+            return Vec::new();
+        };
+        let mut result: Vec<(String, f32)> = self
+            .vocab
+            .iter()
+            .enumerate()
+            .filter(|(_, other)| other.as_str() != word)
+            .map(|(i, other)| {
+                let vector = self.vectors.row(i).to_owned();
+                (other.clone(), cosine(&target, &vector))
+            })
+            .collect();
+        result.sort_by(|a, b| b.1.total_cmp(&a.1));
+        result.truncate(n);
+        result
+    }
+
+    // 語ベクトルの平均を文書ベクトルとする
+    pub fn document_vector(&self, tokens: &[String]) -> Array1<f32> {
+        let mut sum = Array1::<f32>::zeros(self.vectors.ncols());
+        let mut count = 0;
+        for token in tokens {
+            if let Some(&i) = self.index.get(token) {
+                sum += &self.vectors.row(i);
+                count += 1;
+            }
+        }
+        if count > 0 {
+            sum /= count as f32;
+        }
+        sum
+    }
+
+    // ページとクエリペアの意味的な類似度
+    pub fn query_similarity(&self, page: &Page, main_query: &str, sub_query: &str) -> f32 {
+        let mut query = query_terms(main_query);
+        query.extend(query_terms(sub_query));
+        cosine(
+            &self.document_vector(&page.tokens()),
+            &self.document_vector(&query),
+        )
+    }
+
+    // strfn::contains_wordの類義語対応版
+    pub fn contains_similar_word(&self, word: &str, tokens: &[String], threshold: f32) -> bool {
+        tokens.iter().any(|token| {
+            token == word
+                || self
+                    .similarity(word, token)
+                    .is_some_and(|sim| sim >= threshold)
+        })
+    }
+
+    // word2vecのテキスト形式で保存する
+    pub fn save(&self, path: &str) {
+        let file = File::create(path).expect("埋め込みファイルを作成できませんでした");
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{} {}", self.vocab.len(), self.vectors.ncols())
+            .expect("埋め込みファイルに書き込めませんでした");
+        for (i, word) in self.vocab.iter().enumerate() {
+            let values: Vec<String> = self.vectors.row(i).iter().map(|v| v.to_string()).collect();
+            writeln!(writer, "{} {}", word, values.join(" "))
+                .expect("埋め込みファイルに書き込めませんでした");
+        }
+    }
+
+    pub fn load(path: &str) -> Self {
+        let file = File::open(path).expect("埋め込みファイルが開けませんでした");
+        let mut lines = BufReader::new(file).lines();
+        let header = lines
+            .next()
+            .expect("埋め込みファイルが空です")
+            .expect("読み取りエラー");
+        let dim: usize = header
+            .split_whitespace()
+            .nth(1)
+            .and_then(|d| d.parse().ok())
+            .expect("埋め込みファイルのヘッダが不正です");
+
+        let mut vocab = Vec::new();
+        let mut values = Vec::new();
+        for line in lines {
+            let line = line.expect("読み取りエラー");
+            let mut parts = line.split(' ');
+            let Some(word) = parts.next() else {
+                continue;
+            };
+            let vector: Vec<f32> = parts.filter_map(|v| v.parse().ok()).collect();
+            if vector.len() == dim {
+                vocab.push(word.to_string());
+                values.extend(vector);
+            }
+        }
+        let index = vocab
+            .iter()
+            .enumerate()
+            .map(|(i, word)| (word.clone(), i))
+            .collect();
+        let vectors =
+            Array2::from_shape_vec((vocab.len(), dim), values).expect("埋め込みの形状が不正です");
+        Embeddings {
+            vocab,
+            index,
+            vectors,
+        }
+    }
+}
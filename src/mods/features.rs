@@ -0,0 +1,86 @@
+use crate::mods::compression;
+use crate::mods::corpus::{self, Page};
+use crate::mods::corpus_stats::{query_terms, CorpusStats, TfWeighting};
+use crate::mods::eeat;
+use crate::mods::structured_data;
+
+// ランキング学習に使うページの特徴量
+#[derive(Debug, Clone, Default)]
+pub struct PageFeatures {
+    pub bm25_main: f64,
+    pub bm25_sub: f64,
+    pub bm25_pair: f64,
+    pub tfidf_main: f64,
+    pub tfidf_sub: f64,
+    pub structured_data: f64,   // 構造化データの最上位のエンティティ数
+    pub rich_result_types: f64, // 必須プロパティを満たす検索機能向けの型の数
+    pub compression_ratio: f64, // 本文のgzip圧縮率 (小さいほど冗長)
+    pub eeat: f64,              // 著者・日付・運営者などの情報の充足度
+    pub citations: f64,         // 信頼できるドメインへのリンク数
+}
+
+impl PageFeatures {
+    pub fn names() -> Vec<&'static str> {
+        vec![
+            "bm25_main",
+            "bm25_sub",
+            "bm25_pair",
+            "tfidf_main",
+            "tfidf_sub",
+            "structured_data",
+            "rich_result_types",
+            "compression_ratio",
+            "eeat",
+            "citations",
+        ]
+    }
+
+    pub fn to_vec(&self) -> Vec<f64> {
+        vec![
+            self.bm25_main,
+            self.bm25_sub,
+            self.bm25_pair,
+            self.tfidf_main,
+            self.tfidf_sub,
+            self.structured_data,
+            self.rich_result_types,
+            self.compression_ratio,
+            self.eeat,
+            self.citations,
+        ]
+    }
+}
+
+// クエリを構成する語のTF-IDFの合計
+fn query_tf_idf(tf_idf: &[(String, f64)], query: &str) -> f64 {
+    let terms = query_terms(query);
+    tf_idf
+        .iter()
+        .filter(|(term, _)| terms.contains(term))
+        .map(|(_, score)| score)
+        .sum()
+}
+
+pub fn page_features(
+    stats: &CorpusStats,
+    page: &Page,
+    main_query: &str,
+    sub_query: &str,
+) -> PageFeatures {
+    let (bm25_main, bm25_sub, bm25_pair) = stats.bm25_pair(page, main_query, sub_query);
+    let tf_idf = stats.tf_idf(page, TfWeighting::Log);
+    let structured = structured_data::extract(&page.path);
+    let signals = eeat::extract(&page.path);
+    PageFeatures {
+        bm25_main,
+        bm25_sub,
+        bm25_pair,
+        tfidf_main: query_tf_idf(&tf_idf, main_query),
+        tfidf_sub: query_tf_idf(&tf_idf, sub_query),
+        structured_data: structured.top_level_count() as f64,
+        rich_result_types: structured.valid_rule_count() as f64,
+        compression_ratio: compression::compression_ratio(&corpus::page_texts(&page.path).concat()),
+        eeat: signals.score(),
+        citations: signals.citations.len() as f64,
+    }
+}
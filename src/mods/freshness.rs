@@ -0,0 +1,376 @@
+use crate::mods::corpus;
+use crate::mods::dates;
+use crate::mods::eeat::{MODIFIED_LABELS, PUBLISHED_LABELS};
+use crate::mods::headers;
+use crate::mods::main_content::SKIP_TAGS;
+use crate::mods::metadata;
+use crate::mods::structured_data::{self, PropValue};
+use chrono::NaiveDate;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+
+// 保存済みのリンク一覧 (./data/0.json は ./db/0.html のリンク)
+pub const LINKS_DIR: &str = "./data";
+// 公開日・更新日を表すmetaタグ
+const PUBLISHED_METAS: [&str; 4] = ["article:published_time", "datePublished", "pubdate", "date"];
+const MODIFIED_METAS: [&str; 3] = ["article:modified_time", "og:updated_time", "dateModified"];
+// 公開日・更新日を表す構造化データのプロパティ
+const PUBLISHED_PROPS: [&str; 3] = ["datePublished", "dateCreated", "uploadDate"];
+const MODIFIED_PROPS: [&str; 1] = ["dateModified"];
+// time要素のclassに含まれる語
+const PUBLISHED_CLASSES: [&str; 4] = ["publish", "posted", "entry-date", "created"];
+const MODIFIED_CLASSES: [&str; 2] = ["update", "modified"];
+// 日付の直前のラベルを探す範囲 (文字数)
+const LABEL_WINDOW: usize = 12;
+
+// 日付を見つけた場所
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSource {
+    StructuredData,
+    MetaTag,
+    TimeElement,
+    UrlPath,
+    BodyText,
+    LastModified, // レスポンスヘッダ
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateKind {
+    Published,
+    Modified,
+    Unknown,
+}
+
+// 日付の候補
+#[derive(Debug, Clone)]
+pub struct DateCandidate {
+    pub date: NaiveDate,
+    pub source: DateSource,
+    pub kind: DateKind,
+    pub confidence: f64,
+}
+
+// 候補から選んだ日付
+#[derive(Debug, Clone)]
+pub struct ResolvedDate {
+    pub date: NaiveDate,
+    pub confidence: f64, // 0～1
+    pub sources: Vec<DateSource>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Freshness {
+    pub published: Option<ResolvedDate>,
+    pub modified: Option<ResolvedDate>,
+    pub candidates: Vec<DateCandidate>,
+    pub lower_bound: Option<NaiveDate>, // リンク先のURLの最も新しい日付 (ページはそれ以降に書かれている)
+}
+
+impl Freshness {
+    // 更新日があれば更新日、なければ公開日
+    pub fn latest(&self) -> Option<&ResolvedDate> {
+        self.modified.as_ref().or(self.published.as_ref())
+    }
+
+    // 指定日からみた経過日数
+    pub fn age_days(&self, today: NaiveDate) -> Option<i64> {
+        self.latest()
+            .map(|resolved| (today - resolved.date).num_days())
+    }
+}
+
+// 場所と種類ごとの確からしさ
+fn base_confidence(source: DateSource, kind: DateKind) -> f64 {
+    match (source, kind) {
+        (DateSource::StructuredData, _) => 0.9,
+        (DateSource::MetaTag, _) => 0.85,
+        (DateSource::TimeElement, DateKind::Unknown) => 0.5,
+        (DateSource::TimeElement, _) => 0.75,
+        (DateSource::UrlPath, _) => 0.6,
+        (DateSource::BodyText, DateKind::Unknown) => 0.2,
+        (DateSource::BodyText, _) => 0.6,
+        (DateSource::LastModified, _) => 0.4,
+    }
+}
+
+fn candidate(date: NaiveDate, source: DateSource, kind: DateKind) -> DateCandidate {
+    DateCandidate {
+        date,
+        source,
+        kind,
+        confidence: base_confidence(source, kind),
+    }
+}
+
+// /2024/10/17/ や /2024/10/ のようなURLのパスの日付
+pub fn url_date(url: &str) -> Option<NaiveDate> {
+    let path = url::Url::parse(url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| url.to_string());
+    let pattern = Regex::new(r"/((?:19|20)\d{2})/(\d{1,2})(?:/(\d{1,2}))?(?:/|$|[-_])").unwrap();
+    let captures = pattern.captures(&path)?;
+    let year = captures[1].parse().ok()?;
+    let month = captures[2].parse().ok()?;
+    let day = captures
+        .get(3)
+        .map_or(Some(1), |day| day.as_str().parse().ok())?;
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+// 保存済みのリンク一覧のうち、URLに日付を含むもの
+pub fn linked_dates(rank: usize) -> Vec<(String, NaiveDate)> {
+    let path = format!("{}/{}.json", LINKS_DIR, rank);
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let links: Vec<String> = serde_json::from_str(&content).expect("リンク一覧の形式が不正です");
+    links
+        .into_iter()
+        .filter_map(|link| {
+            let date = url_date(&link)?;
+            Some((link, date))
+        })
+        .collect()
+}
+
+// Last-Modifiedヘッダ (RFC 2822の形式)
+pub fn last_modified(html_path: &str) -> Option<NaiveDate> {
+    let value = headers::load_headers(html_path).remove("last-modified")?;
+    chrono::DateTime::parse_from_rfc2822(value.trim())
+        .ok()
+        .map(|datetime| datetime.date_naive())
+}
+
+// 直前の文字列のうち最も近いラベルから種類を決める
+fn label_kind(prefix: &str) -> DateKind {
+    let nearest = |labels: &[&str]| labels.iter().filter_map(|label| prefix.rfind(label)).max();
+    match (nearest(&PUBLISHED_LABELS), nearest(&MODIFIED_LABELS)) {
+        (Some(published), Some(modified)) if published > modified => DateKind::Published,
+        (_, Some(_)) => DateKind::Modified,
+        (Some(_), None) => DateKind::Published,
+        (None, None) => DateKind::Unknown,
+    }
+}
+
+fn class_kind(names: &str) -> DateKind {
+    let names = names.to_lowercase();
+    if MODIFIED_CLASSES.iter().any(|name| names.contains(name)) {
+        DateKind::Modified
+    } else if PUBLISHED_CLASSES.iter().any(|name| names.contains(name)) {
+        DateKind::Published
+    } else {
+        DateKind::Unknown
+    }
+}
+
+fn time_candidates(document: &Html) -> Vec<DateCandidate> {
+    let time_selector = Selector::parse("time").unwrap();
+    document
+        .select(&time_selector)
+        .filter_map(|time| {
+            let value = time.value();
+            let text = time.text().collect::<String>();
+            let date = dates::parse_date(value.attr("datetime").unwrap_or(&text))?;
+            let names = format!(
+                "{} {} {}",
+                value.attr("itemprop").unwrap_or(""),
+                value.attr("class").unwrap_or(""),
+                value.attr("pubdate").map_or("", |_| "publish")
+            );
+            let kind = match class_kind(&names) {
+                DateKind::Unknown => label_kind(&text),
+                kind => kind,
+            };
+            Some(candidate(date, DateSource::TimeElement, kind))
+        })
+        .collect()
+}
+
+fn structured_candidates(html_content: &str) -> Vec<DateCandidate> {
+    let data = structured_data::extract_html(html_content);
+    let mut candidates = Vec::new();
+    for entity in &data.entities {
+        for (props, kind) in [
+            (&PUBLISHED_PROPS[..], DateKind::Published),
+            (&MODIFIED_PROPS[..], DateKind::Modified),
+        ] {
+            for prop in props {
+                let values = entity.properties.get(*prop).into_iter().flatten();
+                for value in values {
+                    if let PropValue::Text(text) = value {
+                        if let Some(date) = dates::parse_date(text) {
+                            candidates.push(candidate(date, DateSource::StructuredData, kind));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    candidates
+}
+
+fn body_candidates(document: &Html) -> Vec<DateCandidate> {
+    let body_selector = Selector::parse("body").unwrap();
+    let Some(body) = document.select(&body_selector).next() else {
+        return Vec::new();
+    };
+    // 要素をまたいで数字がつながらないようにテキストノードごとに改行で区切る
+    let texts: Vec<&str> = body
+        .descendants()
+        .filter_map(|node| {
+            let Node::Text(text) = node.value() else {
+                return None;
+            };
+            let parent = node.parent().and_then(ElementRef::wrap)?;
+            (!SKIP_TAGS.contains(&parent.value().name())).then_some(&**text)
+        })
+        .collect();
+    let text = dates::normalize_digits(&texts.join("\n"));
+    dates::find_dates(&text)
+        .into_iter()
+        .map(|found| {
+            let prefix: String = text[..found.start]
+                .chars()
+                .rev()
+                .take(LABEL_WINDOW)
+                .collect::<Vec<char>>()
+                .into_iter()
+                .rev()
+                .collect();
+            candidate(found.date, DateSource::BodyText, label_kind(&prefix))
+        })
+        .collect()
+}
+
+// 1 - Π(1 - 確からしさ) で同じ日付を示す候補をまとめる
+fn combined_confidence(candidates: &[&DateCandidate]) -> f64 {
+    1.0 - candidates
+        .iter()
+        .map(|candidate| 1.0 - candidate.confidence)
+        .product::<f64>()
+}
+
+// 同じ日付の候補の確からしさをまとめ、最も高い日付を選ぶ
+// 他の日付を示す候補が多いほど確からしさを下げる
+fn resolve(candidates: &[&DateCandidate]) -> Option<ResolvedDate> {
+    let mut dates: Vec<NaiveDate> = candidates.iter().map(|candidate| candidate.date).collect();
+    dates.sort();
+    dates.dedup();
+    let total: f64 = candidates
+        .iter()
+        .map(|candidate| candidate.confidence)
+        .sum();
+    dates
+        .into_iter()
+        .map(|date| {
+            let agreeing: Vec<&DateCandidate> = candidates
+                .iter()
+                .copied()
+                .filter(|candidate| candidate.date == date)
+                .collect();
+            let weight: f64 = agreeing.iter().map(|candidate| candidate.confidence).sum();
+            // 見つけた順のまま重複を除く
+            let mut sources: Vec<DateSource> = Vec::new();
+            for candidate in &agreeing {
+                if !sources.contains(&candidate.source) {
+                    sources.push(candidate.source);
+                }
+            }
+            ResolvedDate {
+                date,
+                confidence: combined_confidence(&agreeing) * weight / total,
+                sources,
+            }
+        })
+        // 同じ確からしさなら古い日付 (先に並んでいる方) を選ぶ
+        .reduce(|best, resolved| {
+            if resolved.confidence > best.confidence {
+                resolved
+            } else {
+                best
+            }
+        })
+}
+
+pub fn analyze_html(html_content: &str, html_path: &str) -> Freshness {
+    let document = Html::parse_document(html_content);
+    let metadata = metadata::parse_metadata_html(html_content);
+    let mut candidates = structured_candidates(html_content);
+
+    for (names, kind) in [
+        (&PUBLISHED_METAS[..], DateKind::Published),
+        (&MODIFIED_METAS[..], DateKind::Modified),
+    ] {
+        for name in names {
+            if let Some(date) = metadata.meta(name).and_then(dates::parse_date) {
+                candidates.push(candidate(date, DateSource::MetaTag, kind));
+            }
+        }
+    }
+    candidates.extend(time_candidates(&document));
+    if let Some(date) = metadata.page_url().and_then(|url| url_date(url.as_str())) {
+        candidates.push(candidate(date, DateSource::UrlPath, DateKind::Published));
+    }
+    candidates.extend(body_candidates(&document));
+    if let Some(date) = last_modified(html_path) {
+        candidates.push(candidate(
+            date,
+            DateSource::LastModified,
+            DateKind::Modified,
+        ));
+    }
+
+    // 未来の日付は除く
+    let today = chrono::Local::now().date_naive();
+    candidates.retain(|candidate| candidate.date <= today);
+
+    // リンク先の日付は公開日の候補にせず、下限としてだけ使う
+    let rank = std::path::Path::new(html_path)
+        .file_stem()
+        .and_then(|stem| stem.to_str()?.parse::<usize>().ok());
+    let lower_bound = rank.and_then(|rank| {
+        linked_dates(rank)
+            .into_iter()
+            .map(|(_, date)| date)
+            .filter(|date| *date <= today)
+            .max()
+    });
+
+    let of_kind = |kinds: &[DateKind]| -> Vec<&DateCandidate> {
+        candidates
+            .iter()
+            .filter(|candidate| kinds.contains(&candidate.kind))
+            .collect()
+    };
+    let published = resolve(&of_kind(&[DateKind::Published]))
+        .or_else(|| resolve(&of_kind(&[DateKind::Unknown])));
+    // 公開日より前の更新日は採用しない
+    let modified = resolve(&of_kind(&[DateKind::Modified])).filter(|modified| {
+        published
+            .as_ref()
+            .is_none_or(|published| modified.date >= published.date)
+    });
+
+    Freshness {
+        published,
+        modified,
+        candidates,
+        lower_bound,
+    }
+}
+
+pub fn analyze(file_path: &str) -> Freshness {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    analyze_html(&html_content, file_path)
+}
+
+// 保存済みの上位ページそれぞれの公開日・更新日
+pub fn top_freshness() -> Vec<(usize, Freshness)> {
+    corpus::TOP_RANKS
+        .filter_map(|rank| {
+            let path = corpus::page_path(rank);
+            let html_content = std::fs::read_to_string(&path).ok()?;
+            Some((rank, analyze_html(&html_content, &path)))
+        })
+        .collect()
+}
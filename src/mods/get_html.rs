@@ -66,19 +66,19 @@ pub struct FormTags {
 // メディア関連のタグをまとめた構造体
 #[derive(Debug, Default)]
 pub struct MediaTags {
-    img: i32,
-    audio: i32,
-    video: i32,
-    source: i32,
-    track: i32,
-    map: i32,
-    area: i32,
-    picture: i32,
-    canvas: i32,
-    svg: i32,
-    object: i32,
-    embed: i32,
-    iframe: i32,
+    pub img: i32,
+    pub audio: i32,
+    pub video: i32,
+    pub source: i32,
+    pub track: i32,
+    pub map: i32,
+    pub area: i32,
+    pub picture: i32,
+    pub canvas: i32,
+    pub svg: i32,
+    pub object: i32,
+    pub embed: i32,
+    pub iframe: i32,
 }
 
 // テーブル関連のタグをまとめた構造体
@@ -142,3 +142,25 @@ pub struct HtmlTags {
     hr: i32,               // 個別の hr タグ
     wbr: i32,              // 個別の wbr タグ
 }
+
+impl MediaTags {
+    // タグ名ごとの出現回数から組み立てる
+    pub fn from_counts(counts: &std::collections::HashMap<String, i32>) -> Self {
+        let count = |tag: &str| counts.get(tag).copied().unwrap_or(0);
+        MediaTags {
+            img: count("img"),
+            audio: count("audio"),
+            video: count("video"),
+            source: count("source"),
+            track: count("track"),
+            map: count("map"),
+            area: count("area"),
+            picture: count("picture"),
+            canvas: count("canvas"),
+            svg: count("svg"),
+            object: count("object"),
+            embed: count("embed"),
+            iframe: count("iframe"),
+        }
+    }
+}
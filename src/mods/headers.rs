@@ -0,0 +1,137 @@
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+// ステータスコードはこのキーで一緒に保存する
+pub const STATUS_KEY: &str = ":status";
+
+// ./db/0.html のレスポンスヘッダは ./db/0.headers.json に保存する
+pub fn headers_path(html_path: &str) -> String {
+    Path::new(html_path)
+        .with_extension("headers.json")
+        .to_string_lossy()
+        .to_string()
+}
+
+// 保存済みのレスポンスヘッダを読み込む (キーは小文字)
+pub fn load_headers(html_path: &str) -> HashMap<String, String> {
+    let Ok(content) = std::fs::read_to_string(headers_path(html_path)) else {
+        return HashMap::new();
+    };
+    let headers: HashMap<String, String> =
+        serde_json::from_str(&content).expect("ヘッダファイルの形式が不正です");
+    headers
+        .into_iter()
+        .map(|(key, value)| (key.to_lowercase(), value))
+        .collect()
+}
+
+// 取得時のステータスコード (ヘッダを保存していなければNone)
+pub fn load_status(html_path: &str) -> Option<u16> {
+    load_headers(html_path).get(STATUS_KEY)?.parse().ok()
+}
+
+pub fn save_headers(html_path: &str, headers: &HashMap<String, String>) {
+    let content = serde_json::to_string_pretty(headers).expect("JSONに変換できませんでした");
+    std::fs::write(headers_path(html_path), content).expect("ヘッダファイルを保存できませんでした");
+}
+
+// Content-Encodingに従って本文を展開する (複数ある場合は後に適用されたものから戻す)
+pub fn decode_body(body: &[u8], content_encoding: Option<&str>) -> Vec<u8> {
+    let mut data = body.to_vec();
+    let encodings: Vec<String> = content_encoding
+        .unwrap_or("")
+        .split(',')
+        .map(|encoding| encoding.trim().to_lowercase())
+        .filter(|encoding| !encoding.is_empty())
+        .collect();
+    for encoding in encodings.iter().rev() {
+        let mut decoded = Vec::new();
+        let result = match encoding.as_str() {
+            "gzip" | "x-gzip" => GzDecoder::new(data.as_slice()).read_to_end(&mut decoded),
+            "deflate" => ZlibDecoder::new(data.as_slice()).read_to_end(&mut decoded),
+            "br" => brotli::Decompressor::new(data.as_slice(), 4096).read_to_end(&mut decoded),
+            // identityや展開できないエンコーディングはそのまま
+            _ => continue,
+        };
+        // 展開に失敗した場合は受け取ったままにする
+        if result.is_err() {
+            return data;
+        }
+        data = decoded;
+    }
+    data
+}
+
+// 取得したページ
+#[derive(Debug, Clone, Default)]
+pub struct Fetched {
+    pub url: String, // リダイレクト後のURL
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>, // 展開済みの本文
+}
+
+impl Fetched {
+    // 2xxで返ってきたか (4xx/5xxもreqwestではOkになる)
+    pub fn is_success(&self) -> bool {
+        self.headers
+            .get(STATUS_KEY)
+            .and_then(|status| status.parse::<u16>().ok())
+            .is_some_and(|status| (200..300).contains(&status))
+    }
+
+    pub fn is_html(&self) -> bool {
+        self.headers
+            .get("content-type")
+            .is_some_and(|content_type| {
+                let content_type = content_type.to_lowercase();
+                content_type.contains("text/html") || content_type.contains("application/xhtml")
+            })
+    }
+
+    // HTMLとレスポンスヘッダを保存する (Shift_JIS等を壊さないよう展開したバイト列のまま)
+    pub fn save(&self, html_path: &str) {
+        std::fs::write(html_path, &self.body).expect("HTMLを保存できませんでした");
+        save_headers(html_path, &self.headers);
+    }
+}
+
+// ページを取得して本文を展開する (ヘッダのキーは小文字)
+pub async fn fetch(url: &str) -> reqwest::Result<Fetched> {
+    let response = reqwest::Client::new()
+        .get(url)
+        .header("Accept-Encoding", "gzip, deflate, br")
+        .send()
+        .await?;
+    let mut headers: HashMap<String, String> = HashMap::new();
+    // 同じ名前のヘッダが複数ある場合はカンマでつなぐ
+    for (key, value) in response.headers() {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        headers
+            .entry(key.as_str().to_string())
+            .and_modify(|joined| *joined = format!("{}, {}", joined, value))
+            .or_insert_with(|| value.to_string());
+    }
+    headers.insert(
+        STATUS_KEY.to_string(),
+        response.status().as_u16().to_string(),
+    );
+    let final_url = response.url().to_string();
+    // reqwestは展開しないので自分で展開する (Content-Encodingは圧縮の確認のため残す)
+    let body = response.bytes().await?;
+    let body = decode_body(&body, headers.get("content-encoding").map(String::as_str));
+    Ok(Fetched {
+        url: final_url,
+        headers,
+        body,
+    })
+}
+
+// ページを取得してHTMLとレスポンスヘッダを保存する
+pub async fn capture(url: &str, html_path: &str) -> reqwest::Result<()> {
+    fetch(url).await?.save(html_path);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use crate::mods::main_content;
+use crate::mods::traits::Formatter;
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+
+// 見出しがあってはいけない領域
+const OUTSIDE_TAGS: [&str; 2] = ["nav", "footer"];
+
+// 見出しツリーの節
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HeadingNode {
+    pub level: u8,
+    pub text: String,
+    pub container: Option<String>, // nav/footer内にある場合のタグ名
+    pub children: Vec<HeadingNode>,
+}
+
+// 見出し構造の問題点
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum OutlineIssue {
+    MissingH1,
+    MultipleH1(usize),
+    SkippedLevel {
+        from: u8,
+        to: u8,
+        text: String,
+    },
+    EmptyHeading {
+        level: u8,
+        index: usize,
+    },
+    InBoilerplate {
+        level: u8,
+        text: String,
+        container: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Outline {
+    pub headings: Vec<HeadingNode>, // 文書順の平坦なリスト (childrenは空)
+    pub roots: Vec<HeadingNode>,
+    pub issues: Vec<OutlineIssue>,
+}
+
+// 平坦な見出しリストを階層に組み立てる
+fn build_tree(headings: &[HeadingNode]) -> Vec<HeadingNode> {
+    let mut roots: Vec<HeadingNode> = Vec::new();
+    let mut stack: Vec<HeadingNode> = Vec::new();
+    for heading in headings {
+        while stack.last().is_some_and(|top| top.level >= heading.level) {
+            let done = stack.pop().unwrap();
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => roots.push(done),
+            }
+        }
+        stack.push(heading.clone());
+    }
+    while let Some(done) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(done),
+            None => roots.push(done),
+        }
+    }
+    roots
+}
+
+fn find_issues(headings: &[HeadingNode]) -> Vec<OutlineIssue> {
+    let mut issues = Vec::new();
+    let h1_count = headings.iter().filter(|h| h.level == 1).count();
+    if h1_count == 0 {
+        issues.push(OutlineIssue::MissingH1);
+    } else if h1_count > 1 {
+        issues.push(OutlineIssue::MultipleH1(h1_count));
+    }
+
+    let mut previous = 0;
+    for (index, heading) in headings.iter().enumerate() {
+        // 見出しレベルを2段以上飛ばして下げている
+        if previous > 0 && heading.level > previous + 1 {
+            issues.push(OutlineIssue::SkippedLevel {
+                from: previous,
+                to: heading.level,
+                text: heading.text.clone(),
+            });
+        }
+        if heading.text.is_empty() {
+            issues.push(OutlineIssue::EmptyHeading {
+                level: heading.level,
+                index,
+            });
+        }
+        if let Some(container) = &heading.container {
+            issues.push(OutlineIssue::InBoilerplate {
+                level: heading.level,
+                text: heading.text.clone(),
+                container: container.clone(),
+            });
+        }
+        previous = heading.level;
+    }
+    issues
+}
+
+pub fn build_outline_html(html_content: &str) -> Outline {
+    let document = Html::parse_document(html_content);
+    let headings: Vec<HeadingNode> = document
+        .tree
+        .nodes()
+        .filter_map(ElementRef::wrap)
+        .filter_map(|element| {
+            let level = main_content::heading_level(element.value().name())?;
+            let container = element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .map(|ancestor| ancestor.value().name().to_string())
+                .find(|name| OUTSIDE_TAGS.contains(&name.as_str()));
+            Some(HeadingNode {
+                level,
+                text: main_content::visible_text(&element)
+                    .replace_ws()
+                    .format_ws(),
+                container,
+                children: Vec::new(),
+            })
+        })
+        .collect();
+
+    Outline {
+        roots: build_tree(&headings),
+        issues: find_issues(&headings),
+        headings,
+    }
+}
+
+pub fn build_outline(file_path: &str) -> Outline {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    build_outline_html(&html_content)
+}
+
+fn render_node(node: &HeadingNode, depth: usize, result: &mut String) {
+    let text = if node.text.is_empty() {
+        "(空)"
+    } else {
+        &node.text
+    };
+    result.push_str(&format!(
+        "{}h{}: {}\n",
+        "  ".repeat(depth),
+        node.level,
+        text
+    ));
+    for child in &node.children {
+        render_node(child, depth + 1, result);
+    }
+}
+
+impl Outline {
+    // インデントしたツリーとして表示する
+    pub fn render_tree(&self) -> String {
+        let mut result = String::new();
+        for root in &self.roots {
+            render_node(root, 0, &mut result);
+        }
+        result
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("JSONに変換できませんでした")
+    }
+
+    // 見出しを降順に設定できているか
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
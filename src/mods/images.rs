@@ -0,0 +1,175 @@
+use scraper::{ElementRef, Html, Selector};
+use std::path::{Path, PathBuf};
+
+// 縦横比のずれの許容範囲
+pub const ASPECT_TOLERANCE: f64 = 0.05;
+// 表示サイズに対して許容する実寸の倍率 (高解像度ディスプレイ向け)
+pub const MAX_DENSITY: f64 = 2.0;
+
+// srcsetの1候補
+#[derive(Debug, Clone, Default)]
+pub struct SrcsetEntry {
+    pub url: String,
+    pub descriptor: Option<String>, // "2x" や "640w"
+}
+
+// ページ内の画像1つ分の情報
+#[derive(Debug, Clone, Default)]
+pub struct ImageInfo {
+    pub tag: String, // img / source
+    pub src: Option<String>,
+    pub srcset: Vec<SrcsetEntry>,
+    pub alt: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub loading: Option<String>,
+    pub decoding: Option<String>,
+    pub in_picture: bool,
+    pub linked: bool,
+    pub in_figure: bool,
+    pub has_figcaption: bool,
+    pub actual_size: Option<(usize, usize)>, // ローカルにある画像の実寸
+}
+
+impl ImageInfo {
+    pub fn has_alt(&self) -> bool {
+        self.alt.as_ref().is_some_and(|alt| !alt.trim().is_empty())
+    }
+
+    pub fn has_dimensions(&self) -> bool {
+        self.width.is_some() && self.height.is_some()
+    }
+
+    pub fn has_caption(&self) -> bool {
+        self.in_figure && self.has_figcaption
+    }
+
+    // 指定サイズと実寸の縦横比が一致しているか (判定できなければNone)
+    pub fn aspect_ratio_ok(&self) -> Option<bool> {
+        let (actual_width, actual_height) = self.actual_size?;
+        let (width, height) = (self.width?, self.height?);
+        if actual_height == 0 || height == 0 {
+            return Some(false);
+        }
+        let actual_ratio = actual_width as f64 / actual_height as f64;
+        let ratio = width as f64 / height as f64;
+        Some((ratio - actual_ratio).abs() / actual_ratio <= ASPECT_TOLERANCE)
+    }
+
+    // 実寸が表示サイズ以上かつ大きすぎないか (判定できなければNone)
+    pub fn resolution_ok(&self) -> Option<bool> {
+        let (actual_width, _) = self.actual_size?;
+        let width = self.width? as f64;
+        let actual_width = actual_width as f64;
+        Some(actual_width >= width && actual_width <= width * MAX_DENSITY)
+    }
+}
+
+fn parse_dimension(value: Option<&str>) -> Option<u32> {
+    let value = value?.trim().trim_end_matches("px");
+    value.parse::<f64>().ok().map(|v| v as u32)
+}
+
+pub fn parse_srcset(value: &str) -> Vec<SrcsetEntry> {
+    value
+        .split(',')
+        .filter_map(|candidate| {
+            let mut parts = candidate.split_whitespace();
+            let url = parts.next()?.to_string();
+            Some(SrcsetEntry {
+                url,
+                descriptor: parts.next().map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+// 画像URLを保存済みHTMLからの相対パスとして解決する
+pub fn local_path(html_path: &str, src: &str) -> Option<PathBuf> {
+    let src = src.trim();
+    if src.is_empty() || src.starts_with("data:") {
+        return None;
+    }
+    let path = if src.starts_with("http://") || src.starts_with("https://") || src.starts_with("//")
+    {
+        let absolute = if src.starts_with("//") {
+            format!("https:{}", src)
+        } else {
+            src.to_string()
+        };
+        url::Url::parse(&absolute).ok()?.path().to_string()
+    } else {
+        src.split(['?', '#']).next().unwrap_or("").to_string()
+    };
+    let base = Path::new(html_path).parent().unwrap_or(Path::new("."));
+    let local = base.join(path.trim_start_matches('/'));
+    local.exists().then_some(local)
+}
+
+fn has_ancestor(element: &ElementRef, name: &str) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|ancestor| ancestor.value().name() == name)
+}
+
+pub fn analyze_images_html(html_content: &str, html_path: &str) -> Vec<ImageInfo> {
+    let document = Html::parse_document(html_content);
+    let selector = Selector::parse("img, picture > source").unwrap();
+    let figcaption = Selector::parse("figcaption").unwrap();
+
+    document
+        .select(&selector)
+        .map(|element| {
+            let value = element.value();
+            let figure = element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|ancestor| ancestor.value().name() == "figure");
+            let src = value
+                .attr("src")
+                .or(value.attr("data-src"))
+                .map(str::to_string);
+            let srcset = value
+                .attr("srcset")
+                .or(value.attr("data-srcset"))
+                .map(parse_srcset)
+                .unwrap_or_default();
+
+            // srcが無ければsrcsetの最初の候補で実寸を調べる
+            let actual_size = src
+                .as_deref()
+                .or(srcset.first().map(|entry| entry.url.as_str()))
+                .and_then(|src| local_path(html_path, src))
+                .and_then(|path| imagesize::size(path).ok())
+                .map(|size| (size.width, size.height));
+
+            ImageInfo {
+                tag: value.name().to_string(),
+                src,
+                srcset,
+                alt: value.attr("alt").map(str::to_string),
+                width: parse_dimension(value.attr("width")),
+                height: parse_dimension(value.attr("height")),
+                loading: value.attr("loading").map(str::to_string),
+                decoding: value.attr("decoding").map(str::to_string),
+                in_picture: has_ancestor(&element, "picture"),
+                linked: has_ancestor(&element, "a"),
+                in_figure: figure.is_some(),
+                has_figcaption: figure
+                    .is_some_and(|figure| figure.select(&figcaption).next().is_some()),
+                actual_size,
+            }
+        })
+        .collect()
+}
+
+pub fn analyze_images(file_path: &str) -> Vec<ImageInfo> {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    analyze_images_html(&html_content, file_path)
+}
+
+// <img>だけを数える (picture内のsourceは同じ画像の候補なので除く)
+pub fn image_count(images: &[ImageInfo]) -> usize {
+    images.iter().filter(|image| image.tag == "img").count()
+}
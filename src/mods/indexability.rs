@@ -0,0 +1,313 @@
+use crate::mods::corpus;
+use crate::mods::headers;
+use crate::mods::metadata::{self, Metadata};
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::path::Path;
+
+// 値を取るrobotsディレクティブ (ユーザーエージェント名と区別する)
+const VALUE_DIRECTIVES: [&str; 4] = [
+    "max-snippet",
+    "max-image-preview",
+    "max-video-preview",
+    "unavailable_after",
+];
+
+// meta robots / googlebot / X-Robots-Tag をまとめたもの
+#[derive(Debug, Clone, Default)]
+pub struct RobotsDirectives {
+    pub noindex: bool,
+    pub nofollow: bool,
+    pub noarchive: bool,
+    pub nosnippet: bool,
+    pub sources: Vec<String>, // 指定元 (meta:robots, header など)
+}
+
+impl RobotsDirectives {
+    fn add(&mut self, source: &str, content: &str) {
+        let mut applied = false;
+        for token in content.split(',') {
+            let mut token = token.trim().to_lowercase();
+            // X-Robots-Tagの "googlebot: noindex" 形式
+            if let Some((agent, rest)) = token.clone().split_once(':') {
+                if !VALUE_DIRECTIVES.contains(&agent.trim()) {
+                    if agent.trim() != "googlebot" {
+                        continue;
+                    }
+                    token = rest.trim().to_string();
+                }
+            }
+            match token.as_str() {
+                "noindex" => self.noindex = true,
+                "nofollow" => self.nofollow = true,
+                "none" => {
+                    self.noindex = true;
+                    self.nofollow = true;
+                }
+                "noarchive" => self.noarchive = true,
+                "nosnippet" => self.nosnippet = true,
+                _ => continue,
+            }
+            applied = true;
+        }
+        if applied {
+            self.sources.push(source.to_string());
+        }
+    }
+}
+
+// インデックスに関する問題点
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexIssue {
+    ErrorStatus(u16),
+    MultipleCanonicals(usize),
+    CanonicalOtherHost(String),
+    CanonicalNotFound { url: String, status: u16 },
+    NoindexWithCanonical(String),
+    InvalidHreflang(String),
+    MissingSelfHreflang,
+    HreflangNoReturn { lang: String, url: String },
+    MissingLang,
+}
+
+// ページがインデックスされるかの判定
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Indexable,
+    NoIndex,
+    Canonicalised(String), // 別のURLが正規化先
+    Error(u16),
+}
+
+#[derive(Debug, Clone)]
+pub struct Indexability {
+    pub url: Option<String>, // 保存元のURL
+    pub canonical: Option<String>,
+    pub hreflang: Vec<(String, String)>, // 言語コードとURL
+    pub robots: RobotsDirectives,
+    pub lang: Option<String>,
+    pub status: Option<u16>,
+    pub issues: Vec<IndexIssue>,
+    pub verdict: Verdict,
+}
+
+impl Indexability {
+    pub fn is_indexable(&self) -> bool {
+        self.verdict == Verdict::Indexable
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.issues.is_empty()
+    }
+}
+
+// 末尾のスラッシュやフラグメントの違いを無視して比較する
+pub fn normalize_url(url: &url::Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    let host = url.host_str().unwrap_or("").trim_start_matches("www.");
+    format!(
+        "{}{}{}",
+        host,
+        url.path().trim_end_matches('/'),
+        url.query().map(|q| format!("?{}", q)).unwrap_or_default()
+    )
+}
+
+fn resolve(base: Option<&url::Url>, href: &str) -> Option<url::Url> {
+    match base {
+        Some(base) => base.join(href).ok(),
+        None => url::Url::parse(href).ok(),
+    }
+}
+
+// "ja" "en-US" "zh-Hant" "x-default" など
+fn valid_hreflang(lang: &str) -> bool {
+    let lang = lang.to_lowercase();
+    if lang == "x-default" {
+        return true;
+    }
+    let mut parts = lang.split('-');
+    let language = parts.next().unwrap_or("");
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| {
+            (2..=4).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+// コーパス内のページのURLと保存先の対応
+pub fn known_pages() -> HashMap<String, String> {
+    (0..=*corpus::BOTTOM_RANKS.end())
+        .map(corpus::page_path)
+        .filter(|path| Path::new(path).exists())
+        .filter_map(|path| {
+            let url = metadata::parse_metadata(&path).page_url()?;
+            Some((normalize_url(&url), path))
+        })
+        .collect()
+}
+
+fn hreflang_links(metadata: &Metadata, base: Option<&url::Url>) -> Vec<(String, String)> {
+    metadata
+        .links_by_rel("alternate")
+        .into_iter()
+        .filter_map(|link| {
+            let lang = link.hreflang.clone()?;
+            let url = resolve(base, &link.href)?;
+            Some((lang, url.to_string()))
+        })
+        .collect()
+}
+
+pub fn analyze_html(
+    html_content: &str,
+    html_path: &str,
+    known: &HashMap<String, String>,
+) -> Indexability {
+    let metadata = metadata::parse_metadata_html(html_content);
+    let document = Html::parse_document(html_content);
+    let html_selector = Selector::parse("html").unwrap();
+    let page_url = metadata.page_url();
+    // canonicalと比べるため、自身のURLは保存元のURLだけを使う
+    let own = metadata
+        .saved_from
+        .as_deref()
+        .and_then(|url| url::Url::parse(url).ok())
+        .map(|url| normalize_url(&url));
+    let response_headers = headers::load_headers(html_path);
+    let mut issues = Vec::new();
+
+    let status = headers::load_status(html_path);
+    if let Some(status) = status.filter(|status| *status >= 400) {
+        issues.push(IndexIssue::ErrorStatus(status));
+    }
+
+    let mut robots = RobotsDirectives::default();
+    for (key, content) in &metadata.metas {
+        if key == "robots" || key == "googlebot" {
+            robots.add(&format!("meta:{}", key), content);
+        }
+    }
+    if let Some(header) = response_headers.get("x-robots-tag") {
+        robots.add("header:x-robots-tag", header);
+    }
+
+    // canonicalは最初の1つだけが使われる
+    let canonicals = metadata.links_by_rel("canonical");
+    if canonicals.len() > 1 {
+        issues.push(IndexIssue::MultipleCanonicals(canonicals.len()));
+    }
+    let canonical_url = canonicals
+        .first()
+        .and_then(|link| resolve(page_url.as_ref(), &link.href));
+    let canonical_elsewhere = canonical_url.as_ref().filter(|canonical| {
+        own.as_ref()
+            .is_some_and(|own| own != &normalize_url(canonical))
+    });
+    if let Some(canonical) = canonical_url.as_ref() {
+        let host = |url: &url::Url| {
+            url.host_str()
+                .unwrap_or("")
+                .trim_start_matches("www.")
+                .to_string()
+        };
+        if let Some(page_url) = &page_url {
+            if host(canonical) != host(page_url) {
+                issues.push(IndexIssue::CanonicalOtherHost(host(canonical)));
+            }
+        }
+        let target_status = known
+            .get(&normalize_url(canonical))
+            .and_then(|path| headers::load_status(path));
+        if let Some(target_status) = target_status.filter(|status| *status >= 400) {
+            issues.push(IndexIssue::CanonicalNotFound {
+                url: canonical.to_string(),
+                status: target_status,
+            });
+        }
+    }
+    if robots.noindex {
+        if let Some(canonical) = canonical_elsewhere {
+            issues.push(IndexIssue::NoindexWithCanonical(canonical.to_string()));
+        }
+    }
+
+    let hreflang = hreflang_links(&metadata, page_url.as_ref());
+    for (lang, _) in &hreflang {
+        if !valid_hreflang(lang) {
+            issues.push(IndexIssue::InvalidHreflang(lang.clone()));
+        }
+    }
+    if let Some(own) = own.as_ref().filter(|_| !hreflang.is_empty()) {
+        let targets: Vec<String> = hreflang
+            .iter()
+            .filter_map(|(_, url)| url::Url::parse(url).ok().map(|url| normalize_url(&url)))
+            .collect();
+        if !targets.contains(own) {
+            issues.push(IndexIssue::MissingSelfHreflang);
+        }
+        // コーパスにある代替ページだけ戻りリンクを確認できる
+        for ((lang, url), target) in hreflang.iter().zip(&targets) {
+            let Some(path) = known.get(target).filter(|_| target != own) else {
+                continue;
+            };
+            let alternate = metadata::parse_metadata(path);
+            let returns = hreflang_links(&alternate, alternate.page_url().as_ref())
+                .iter()
+                .filter_map(|(_, url)| url::Url::parse(url).ok())
+                .any(|url| &normalize_url(&url) == own);
+            if !returns {
+                issues.push(IndexIssue::HreflangNoReturn {
+                    lang: lang.clone(),
+                    url: url.clone(),
+                });
+            }
+        }
+    }
+
+    let lang = document
+        .select(&html_selector)
+        .next()
+        .and_then(|html| html.value().attr("lang"))
+        .map(|lang| lang.trim().to_string())
+        .filter(|lang| !lang.is_empty());
+    if lang.is_none() {
+        issues.push(IndexIssue::MissingLang);
+    }
+
+    let verdict = match (status, robots.noindex, canonical_elsewhere) {
+        (Some(status), _, _) if status >= 400 => Verdict::Error(status),
+        (_, true, _) => Verdict::NoIndex,
+        (_, _, Some(canonical)) => Verdict::Canonicalised(canonical.to_string()),
+        _ => Verdict::Indexable,
+    };
+
+    Indexability {
+        url: metadata.saved_from.clone(),
+        canonical: canonical_url.map(|url| url.to_string()),
+        hreflang,
+        robots,
+        lang,
+        status,
+        issues,
+        verdict,
+    }
+}
+
+pub fn analyze(file_path: &str) -> Indexability {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    analyze_html(&html_content, file_path, &known_pages())
+}
+
+// コーパスの各ページの判定 (順位と結果の組)
+pub fn corpus_verdicts() -> Vec<(usize, Indexability)> {
+    let known = known_pages();
+    (0..=*corpus::BOTTOM_RANKS.end())
+        .filter_map(|rank| {
+            let path = corpus::page_path(rank);
+            let html_content = std::fs::read_to_string(&path).ok()?;
+            Some((rank, analyze_html(&html_content, &path, &known)))
+        })
+        .collect()
+}
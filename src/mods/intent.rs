@@ -0,0 +1,441 @@
+use crate::mods::corpus;
+use crate::mods::dom_counter;
+use crate::mods::heading_outline;
+use crate::mods::main_content;
+use crate::mods::metadata;
+use crate::mods::structured_data;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const QUERY_PAIRS_PATH: &str = "./sample.json";
+pub const LABELS_PATH: &str = "./data/intent_labels.json";
+pub const MODEL_PATH: &str = "./data/intent_model.json";
+
+// 検索意図
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Intent {
+    Know,
+    Do,
+    Go,
+    Buy,
+}
+
+impl Intent {
+    pub const ALL: [Intent; 4] = [Intent::Know, Intent::Do, Intent::Go, Intent::Buy];
+
+    pub fn index(self) -> usize {
+        match self {
+            Intent::Know => 0,
+            Intent::Do => 1,
+            Intent::Go => 2,
+            Intent::Buy => 3,
+        }
+    }
+}
+
+// クエリに含まれる意図の手がかり
+const CUE_WORDS: [(Intent, &[&str]); 4] = [
+    (
+        Intent::Know,
+        &["とは", "意味", "理由", "なぜ", "違い", "歴史", "種類"],
+    ),
+    (
+        Intent::Do,
+        &[
+            "方法",
+            "やり方",
+            "手順",
+            "申し込み",
+            "登録",
+            "ダウンロード",
+            "使い方",
+        ],
+    ),
+    (
+        Intent::Go,
+        &[
+            "ログイン",
+            "公式",
+            "サイト",
+            "アクセス",
+            "マイページ",
+            "店舗",
+        ],
+    ),
+    (
+        Intent::Buy,
+        &[
+            "購入",
+            "通販",
+            "価格",
+            "値段",
+            "安い",
+            "比較",
+            "おすすめ",
+            "求人",
+        ],
+    ),
+];
+// 手順を説明する見出しの語
+const HOWTO_WORDS: [&str; 6] = ["手順", "方法", "やり方", "ステップ", "step", "使い方"];
+
+// sample.jsonの1件 (第一クエリと第二クエリの候補)
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuerySet {
+    pub main_word: String,
+    pub sub_word: Vec<String>,
+}
+
+// 学習用のラベル付きデータ1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelledQuery {
+    pub main_word: String,
+    pub sub_word: String,
+    pub intent: Intent,
+    pub db_dir: Option<String>, // SERPの保存先 (省略時は./db)
+}
+
+// クエリとSERPから求めた特徴量
+#[derive(Debug, Clone, Default)]
+pub struct IntentFeatures {
+    pub form: f64,        // 入力フォームのあるページの割合
+    pub price: f64,       // 価格の表記があるページの割合
+    pub job_posting: f64, // JobPostingを持つページの割合
+    pub howto: f64,       // 手順を説明しているページの割合
+    pub article: f64,     // Articleを持つページの割合
+    pub brand: f64,       // 最も多いホストが占める割合
+    pub cues: [f64; 4],   // クエリに含まれる手がかりの語 (know/do/go/buy)
+}
+
+impl IntentFeatures {
+    pub fn names() -> Vec<&'static str> {
+        vec![
+            "form",
+            "price",
+            "job_posting",
+            "howto",
+            "article",
+            "brand",
+            "cue_know",
+            "cue_do",
+            "cue_go",
+            "cue_buy",
+        ]
+    }
+
+    pub fn to_vec(&self) -> Vec<f64> {
+        let mut values = vec![
+            self.form,
+            self.price,
+            self.job_posting,
+            self.howto,
+            self.article,
+            self.brand,
+        ];
+        values.extend(self.cues);
+        values
+    }
+}
+
+// ソフトマックス回帰の学習設定
+#[derive(Debug, Clone)]
+pub struct IntentConfig {
+    pub epochs: usize,
+    pub learning_rate: f64,
+    pub l2: f64,
+}
+
+impl Default for IntentConfig {
+    fn default() -> Self {
+        IntentConfig {
+            epochs: 500,
+            learning_rate: 0.5,
+            l2: 0.01,
+        }
+    }
+}
+
+// 学習済みの分類器 (意図ごとの重みとバイアス)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntentModel {
+    pub weights: Vec<Vec<f64>>,
+    pub bias: Vec<f64>,
+}
+
+pub fn load_query_sets(path: &str) -> Vec<QuerySet> {
+    let content = std::fs::read_to_string(path).expect("Failed to read file");
+    serde_json::from_str(&content).expect("クエリファイルの形式が不正です")
+}
+
+pub fn load_labels(path: &str) -> Vec<LabelledQuery> {
+    let content = std::fs::read_to_string(path).expect("Failed to read file");
+    serde_json::from_str(&content).expect("ラベルファイルの形式が不正です")
+}
+
+fn has_price(text: &str) -> bool {
+    let price =
+        Regex::new(r"[¥￥]\s*[0-9０-９][0-9０-９,，]*|[0-9０-９][0-9０-９,，]*\s*円|税込|送料")
+            .unwrap();
+    price.is_match(text)
+}
+
+fn ratio(pages: &[String], matches: impl Fn(&str) -> bool) -> f64 {
+    if pages.is_empty() {
+        return 0.0;
+    }
+    pages.iter().filter(|path| matches(path)).count() as f64 / pages.len() as f64
+}
+
+// 最も多く出現するホストの割合
+fn brand_dominance(pages: &[String]) -> f64 {
+    let mut hosts: HashMap<String, usize> = HashMap::new();
+    for path in pages {
+        if let Some(host) = metadata::parse_metadata(path).page_url().and_then(|url| {
+            url.host_str()
+                .map(|host| host.trim_start_matches("www.").to_string())
+        }) {
+            *hosts.entry(host).or_insert(0) += 1;
+        }
+    }
+    let max = hosts.values().copied().max().unwrap_or(0);
+    max as f64 / pages.len().max(1) as f64
+}
+
+pub fn query_cues(main_query: &str, sub_query: &str) -> [f64; 4] {
+    let query = format!("{} {}", main_query, sub_query).to_lowercase();
+    let mut cues = [0.0; 4];
+    for (intent, words) in CUE_WORDS {
+        if words.iter().any(|word| query.contains(word)) {
+            cues[intent.index()] = 1.0;
+        }
+    }
+    cues
+}
+
+// 保存済みSERPの上位ページから特徴量を求める
+pub fn serp_features(db_dir: &str, main_query: &str, sub_query: &str) -> IntentFeatures {
+    let pages: Vec<String> = corpus::TOP_RANKS
+        .map(|rank| format!("{}/{}.html", db_dir, rank))
+        .filter(|path| Path::new(path).exists())
+        .collect();
+
+    IntentFeatures {
+        form: ratio(&pages, |path| {
+            let counts = dom_counter::count_tags(path);
+            let count = |tag: &str| counts.get(tag).copied().unwrap_or(0);
+            // 検索窓だけのページを除くため入力欄が複数あるものに限る
+            count("form") > 0 && count("input") + count("select") + count("textarea") > 2
+        }),
+        price: ratio(&pages, |path| {
+            has_price(&main_content::extract_main(path).text())
+                || structured_data::extract(path).has_type("Offer")
+        }),
+        job_posting: ratio(&pages, |path| {
+            structured_data::extract(path).has_type("JobPosting")
+        }),
+        howto: ratio(&pages, |path| {
+            structured_data::extract(path).has_type("HowTo")
+                || heading_outline::build_outline(path)
+                    .headings
+                    .iter()
+                    .any(|heading| {
+                        let text = heading.text.to_lowercase();
+                        HOWTO_WORDS.iter().any(|word| text.contains(word))
+                    })
+        }),
+        article: ratio(&pages, |path| {
+            let data = structured_data::extract(path);
+            ["Article", "NewsArticle", "BlogPosting"]
+                .iter()
+                .any(|t| data.has_type(t))
+        }),
+        brand: brand_dominance(&pages),
+        cues: query_cues(main_query, sub_query),
+    }
+}
+
+fn softmax(scores: &[f64]) -> Vec<f64> {
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = scores.iter().map(|score| (score - max).exp()).collect();
+    let total: f64 = exps.iter().sum();
+    exps.iter().map(|e| e / total).collect()
+}
+
+impl IntentModel {
+    // 学習データが無い場合の初期値 (手がかりの語だけで判定する)
+    pub fn cue_only() -> Self {
+        let dim = IntentFeatures::names().len();
+        let mut weights = vec![vec![0.0; dim]; Intent::ALL.len()];
+        for intent in Intent::ALL {
+            weights[intent.index()][dim - 4 + intent.index()] = 1.0;
+        }
+        IntentModel {
+            weights,
+            // 手がかりが無ければ情報収集とみなす
+            bias: vec![0.1, 0.0, 0.0, 0.0],
+        }
+    }
+
+    // 確率的勾配降下法でソフトマックス回帰を学習する
+    pub fn train(samples: &[(IntentFeatures, Intent)], config: &IntentConfig) -> Self {
+        let dim = IntentFeatures::names().len();
+        let mut model = IntentModel {
+            weights: vec![vec![0.0; dim]; Intent::ALL.len()],
+            bias: vec![0.0; Intent::ALL.len()],
+        };
+        let inputs: Vec<(Vec<f64>, usize)> = samples
+            .iter()
+            .map(|(features, intent)| (features.to_vec(), intent.index()))
+            .collect();
+        for _ in 0..config.epochs {
+            for (x, label) in &inputs {
+                let probs = model.probabilities_of(x);
+                for (class, prob) in probs.iter().enumerate() {
+                    let error = prob - if class == *label { 1.0 } else { 0.0 };
+                    for (weight, value) in model.weights[class].iter_mut().zip(x) {
+                        *weight -= config.learning_rate * (error * value + config.l2 * *weight);
+                    }
+                    model.bias[class] -= config.learning_rate * error;
+                }
+            }
+        }
+        model
+    }
+
+    fn probabilities_of(&self, x: &[f64]) -> Vec<f64> {
+        let scores: Vec<f64> = self
+            .weights
+            .iter()
+            .zip(&self.bias)
+            .map(|(weights, bias)| weights.iter().zip(x).map(|(w, v)| w * v).sum::<f64>() + bias)
+            .collect();
+        softmax(&scores)
+    }
+
+    pub fn probabilities(&self, features: &IntentFeatures) -> Vec<(Intent, f64)> {
+        Intent::ALL
+            .into_iter()
+            .zip(self.probabilities_of(&features.to_vec()))
+            .collect()
+    }
+
+    pub fn predict(&self, features: &IntentFeatures) -> Intent {
+        self.probabilities(features)
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(intent, _)| intent)
+            .unwrap_or(Intent::Know)
+    }
+
+    pub fn save(&self, path: &str) {
+        let content = serde_json::to_string_pretty(self).expect("JSONに変換できませんでした");
+        std::fs::write(path, content).expect("モデルファイルを保存できませんでした");
+    }
+
+    // 保存済みのモデルが無ければ手がかりの語だけのモデルを使う
+    pub fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).expect("モデルファイルの形式が不正です"),
+            Err(_) => IntentModel::cue_only(),
+        }
+    }
+}
+
+// ラベル付きファイルから学習する
+pub fn train_from_file(path: &str, config: &IntentConfig) -> IntentModel {
+    let samples: Vec<(IntentFeatures, Intent)> = load_labels(path)
+        .into_iter()
+        .map(|label| {
+            let db_dir = label.db_dir.as_deref().unwrap_or(corpus::DB_DIR);
+            (
+                serp_features(db_dir, &label.main_word, &label.sub_word),
+                label.intent,
+            )
+        })
+        .collect();
+    IntentModel::train(&samples, config)
+}
+
+// SERPの無いクエリ用に、手がかりの語だけでラベル付きファイルから学習する
+pub fn train_cues_from_file(path: &str, config: &IntentConfig) -> IntentModel {
+    let samples: Vec<(IntentFeatures, Intent)> = load_labels(path)
+        .into_iter()
+        .map(|label| {
+            let features = IntentFeatures {
+                cues: query_cues(&label.main_word, &label.sub_word),
+                ..Default::default()
+            };
+            (features, label.intent)
+        })
+        .collect();
+    IntentModel::train(&samples, config)
+}
+
+// 保存済みSERPのクエリの意図を判定する
+pub fn classify(main_query: &str, sub_query: &str) -> Intent {
+    IntentModel::load(MODEL_PATH).predict(&serp_features(corpus::DB_DIR, main_query, sub_query))
+}
+
+// sample.jsonのクエリの組を判定する (SERPが無いのでクエリの手がかりだけを使う)
+// SERPの特徴量で学習したモデルでは全て0の特徴量を判定することになるため、
+// modelはIntentModel::cue_onlyかtrain_cues_from_fileで作ったものを渡す
+pub fn classify_query_sets(path: &str, model: &IntentModel) -> Vec<(String, String, Intent)> {
+    let mut result = Vec::new();
+    for set in load_query_sets(path) {
+        for sub_word in &set.sub_word {
+            let features = IntentFeatures {
+                cues: query_cues(&set.main_word, sub_word),
+                ..Default::default()
+            };
+            result.push((
+                set.main_word.clone(),
+                sub_word.clone(),
+                model.predict(&features),
+            ));
+        }
+    }
+    result
+}
+
+// チェック項目ごとの意図別の重み (know, do, go, buy)
+const RULE_WEIGHTS: [(&str, [f64; 4]); 10] = [
+    ("text_length", [2.0, 1.0, 0.5, 1.0]),
+    ("query_count", [1.5, 1.0, 0.5, 1.0]),
+    ("headings_in_order", [1.5, 1.5, 0.5, 1.0]),
+    ("image_count", [1.0, 1.5, 0.5, 2.0]),
+    ("structured_data", [1.0, 1.5, 1.0, 2.0]),
+    ("readability", [2.0, 1.5, 0.5, 1.0]),
+    ("performance", [1.0, 1.5, 2.0, 2.0]),
+    ("mobile", [1.0, 1.5, 2.0, 2.0]),
+    ("tap_targets", [0.5, 2.0, 1.5, 2.0]),
+    ("buttons_named", [0.5, 2.0, 1.0, 2.0]),
+];
+
+// 表にない項目は1.0とする
+pub fn rule_weight(rule: &str, intent: Intent) -> f64 {
+    RULE_WEIGHTS
+        .iter()
+        .find(|(name, _)| *name == rule)
+        .map(|(_, weights)| weights[intent.index()])
+        .unwrap_or(1.0)
+}
+
+// 満たした項目の重みの合計を100点満点にする
+pub fn weighted_score(results: &[(&str, bool)], intent: Intent) -> f64 {
+    let total: f64 = results
+        .iter()
+        .map(|(rule, _)| rule_weight(rule, intent))
+        .sum();
+    if total == 0.0 {
+        return 0.0;
+    }
+    let passed: f64 = results
+        .iter()
+        .filter(|(_, passed)| *passed)
+        .map(|(rule, _)| rule_weight(rule, intent))
+        .sum();
+    passed / total * 100.0
+}
@@ -0,0 +1,349 @@
+use crate::mods::traits::Formatter;
+use scraper::{ElementRef, Html, Node};
+use std::collections::HashMap;
+
+// 本文として扱わないタグ
+pub const SKIP_TAGS: [&str; 7] = [
+    "script", "style", "noscript", "template", "svg", "iframe", "head",
+];
+// ナビゲーションなど本文の外にあるタグ
+const BOILERPLATE_TAGS: [&str; 5] = ["nav", "aside", "footer", "header", "form"];
+// 中のheaderを記事の一部とみなすタグ
+const SECTIONING_TAGS: [&str; 3] = ["article", "main", "section"];
+// 本文候補になるタグ
+const CANDIDATE_TAGS: [&str; 6] = ["main", "article", "section", "div", "td", "body"];
+// 段落として数えるタグ
+const PARAGRAPH_TAGS: [&str; 3] = ["p", "pre", "blockquote"];
+// まとまったテキストとして取り出すタグ
+const TEXT_BLOCK_TAGS: [&str; 10] = [
+    "p",
+    "li",
+    "pre",
+    "blockquote",
+    "dd",
+    "dt",
+    "td",
+    "th",
+    "figcaption",
+    "caption",
+];
+
+// class/idに含まれると加点・減点する語
+const POSITIVE_HINTS: [&str; 7] = [
+    "content", "main", "article", "post", "entry", "body", "text",
+];
+const NEGATIVE_HINTS: [&str; 16] = [
+    "nav",
+    "menu",
+    "footer",
+    "sidebar",
+    "side",
+    "banner",
+    "cookie",
+    "ad",
+    "ads",
+    "comment",
+    "breadcrumb",
+    "share",
+    "social",
+    "related",
+    "header",
+    "widget",
+];
+
+// 本文を構成するブロックの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Heading(u8),
+    Text,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentBlock {
+    pub kind: BlockKind,
+    pub text: String,
+}
+
+// 抽出した本文
+#[derive(Debug, Clone, Default)]
+pub struct MainContent {
+    pub root_tag: String,
+    pub blocks: Vec<ContentBlock>,
+}
+
+impl MainContent {
+    // 本文のテキストを要素ごとに返す
+    pub fn texts(&self) -> Vec<String> {
+        self.blocks.iter().map(|block| block.text.clone()).collect()
+    }
+
+    pub fn text(&self) -> String {
+        self.texts().join("\n")
+    }
+
+    // 見出しを (レベル, テキスト) で順番通りに返す
+    pub fn headings(&self) -> Vec<(u8, String)> {
+        self.blocks
+            .iter()
+            .filter_map(|block| match block.kind {
+                BlockKind::Heading(level) => Some((level, block.text.clone())),
+                BlockKind::Text => None,
+            })
+            .collect()
+    }
+}
+
+// h1～h6の見出しレベル
+pub fn heading_level(name: &str) -> Option<u8> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+// class/idの語から重みを決める
+fn hint_weight(element: &ElementRef) -> f64 {
+    let value = element.value();
+    let names = format!(
+        "{} {}",
+        value.attr("class").unwrap_or(""),
+        value.attr("id").unwrap_or("")
+    )
+    .to_lowercase();
+    let words: Vec<&str> = names
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut weight = 1.0;
+    if words.iter().any(|word| POSITIVE_HINTS.contains(word)) {
+        weight *= 1.5;
+    }
+    if words.iter().any(|word| NEGATIVE_HINTS.contains(word)) {
+        weight *= 0.3;
+    }
+    weight
+}
+
+fn tag_weight(name: &str) -> f64 {
+    match name {
+        "main" | "article" => 1.5,
+        "section" => 1.1,
+        "nav" | "aside" | "footer" | "header" => 0.1,
+        _ => 1.0,
+    }
+}
+
+// 非表示の要素かどうか
+pub fn is_hidden(element: &ElementRef) -> bool {
+    let value = element.value();
+    let style = value
+        .attr("style")
+        .unwrap_or("")
+        .replace(' ', "")
+        .to_lowercase();
+    value.attr("hidden").is_some()
+        || value.attr("aria-hidden") == Some("true")
+        || style.contains("display:none")
+        || style.contains("visibility:hidden")
+}
+
+// script等を除いた表示テキスト
+pub fn visible_text(element: &ElementRef) -> String {
+    let mut text = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(content) => text.push_str(content),
+            Node::Element(child_element) => {
+                if SKIP_TAGS.contains(&child_element.name()) {
+                    continue;
+                }
+                if let Some(child_ref) = ElementRef::wrap(child) {
+                    text.push_str(&visible_text(&child_ref));
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn normalize(text: &str) -> String {
+    text.replace_ws().format_ws()
+}
+
+// 要素内のテキストのうちリンクが占める割合
+pub fn link_density(element: &ElementRef) -> f64 {
+    let total = normalize(&visible_text(element)).chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let link_chars: usize = element
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|child| child.value().name() == "a")
+        .map(|link| normalize(&visible_text(&link)).chars().count())
+        .sum();
+    (link_chars as f64 / total as f64).min(1.0)
+}
+
+// 段落のスコアを親と祖父母に配り、最もスコアの高い要素を本文とする
+fn best_candidate(document: &Html) -> Option<ElementRef<'_>> {
+    let mut scores = HashMap::new();
+    for node in document.tree.nodes() {
+        let Some(element) = ElementRef::wrap(node) else {
+            continue;
+        };
+        if !PARAGRAPH_TAGS.contains(&element.value().name()) {
+            continue;
+        }
+        let text = normalize(&visible_text(&element));
+        let length = text.chars().count();
+        if length < 25 {
+            continue;
+        }
+        let commas = text.matches(['、', ',', '。']).count() as f64;
+        let score = 1.0 + commas + (length as f64 / 100.0).min(3.0);
+
+        let mut ancestors = element.ancestors().filter_map(ElementRef::wrap);
+        if let Some(parent) = ancestors.next() {
+            *scores.entry(parent.id()).or_insert(0.0) += score;
+        }
+        if let Some(grandparent) = ancestors.next() {
+            *scores.entry(grandparent.id()).or_insert(0.0) += score / 2.0;
+        }
+    }
+
+    let mut best: Option<(ElementRef, f64)> = None;
+    for (id, score) in scores {
+        let Some(element) = document.tree.get(id).and_then(ElementRef::wrap) else {
+            continue;
+        };
+        let name = element.value().name();
+        if !CANDIDATE_TAGS.contains(&name) {
+            continue;
+        }
+        let score =
+            score * tag_weight(name) * hint_weight(&element) * (1.0 - link_density(&element));
+        if best
+            .as_ref()
+            .is_none_or(|(_, best_score)| score > *best_score)
+        {
+            best = Some((element, score));
+        }
+    }
+    best.map(|(element, _)| element)
+}
+
+// article等の中のheaderは記事の見出し部分で、ページのヘッダではない
+fn is_section_header(element: &ElementRef) -> bool {
+    element.value().name() == "header"
+        && element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|ancestor| SECTIONING_TAGS.contains(&ancestor.value().name()))
+}
+
+// 本文の要素からナビゲーション等を除いて見出しとテキストを取り出す
+fn collect_blocks(element: &ElementRef, blocks: &mut Vec<ContentBlock>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let name = child.value().name();
+        let boilerplate = !is_section_header(&child)
+            && (BOILERPLATE_TAGS.contains(&name) || hint_weight(&child) < 1.0);
+        if SKIP_TAGS.contains(&name) || boilerplate || is_hidden(&child) {
+            continue;
+        }
+        if let Some(level) = heading_level(name) {
+            let text = normalize(&visible_text(&child));
+            if !text.is_empty() {
+                blocks.push(ContentBlock {
+                    kind: BlockKind::Heading(level),
+                    text,
+                });
+            }
+        } else if TEXT_BLOCK_TAGS.contains(&name) {
+            let text = normalize(&visible_text(&child));
+            if !text.is_empty() && link_density(&child) < 0.5 {
+                blocks.push(ContentBlock {
+                    kind: BlockKind::Text,
+                    text,
+                });
+            }
+        } else {
+            collect_blocks(&child, blocks);
+        }
+    }
+}
+
+pub fn extract_main_html(html_content: &str) -> MainContent {
+    let document = Html::parse_document(html_content);
+    let root = best_candidate(&document).unwrap_or_else(|| document.root_element());
+
+    let mut blocks = Vec::new();
+    collect_blocks(&root, &mut blocks);
+    // 本文の外 (または除いた部分) にあるh1はタイトルとして先頭に含める
+    let has_h1 = blocks
+        .iter()
+        .any(|block| block.kind == BlockKind::Heading(1));
+    if !has_h1 {
+        let h1 = document
+            .tree
+            .nodes()
+            .filter_map(ElementRef::wrap)
+            .find(|element| element.value().name() == "h1");
+        if let Some(h1) = h1 {
+            let text = normalize(&visible_text(&h1));
+            if !text.is_empty() {
+                blocks.insert(
+                    0,
+                    ContentBlock {
+                        kind: BlockKind::Heading(1),
+                        text,
+                    },
+                );
+            }
+        }
+    }
+
+    MainContent {
+        root_tag: root.value().name().to_string(),
+        blocks,
+    }
+}
+
+pub fn extract_main(file_path: &str) -> MainContent {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    extract_main_html(&html_content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // article内のheaderにあるh1は本文の見出しとして残す
+    #[test]
+    fn keeps_h1_in_article_header() {
+        let html = r#"<html><body>
+            <header class="site-header"><a href="/">サイト名</a></header>
+            <article>
+                <header class="entry-header"><h1>記事のタイトル</h1></header>
+                <p>本文の段落です。これは十分に長い段落で、本文として選ばれるはずです。</p>
+                <p>二つ目の段落もあります。こちらも長めの文章にしておきます。</p>
+            </article>
+        </body></html>"#;
+        let content = extract_main_html(html);
+        let h1: Vec<&ContentBlock> = content
+            .blocks
+            .iter()
+            .filter(|block| block.kind == BlockKind::Heading(1))
+            .collect();
+        assert_eq!(h1.len(), 1);
+        assert_eq!(h1[0].text, "記事のタイトル");
+        assert!(!content.text().contains("サイト名"));
+    }
+}
@@ -1,4 +1,18 @@
+use crate::mods::accessibility::{self, A11yIssue};
+use crate::mods::compression;
+use crate::mods::corpus::{self, Scope};
+use crate::mods::eeat;
 use crate::mods::extract;
+use crate::mods::freshness;
+use crate::mods::heading_outline;
+use crate::mods::images;
+use crate::mods::indexability;
+use crate::mods::metadata;
+use crate::mods::mobile;
+use crate::mods::performance;
+use crate::mods::strfn;
+use crate::mods::text_quality;
+use crate::mods::text_streams;
 use regex::Regex;
 use std::fs::File;
 use std::io::{self, Read};
@@ -8,7 +22,11 @@ const FILE_PATH: &str = "./db/0.html";
 
 pub fn title40() -> bool {
     let title = extract::extract_element("title", FILE_PATH);
-    let title = title[0].to_string();
+    title_within_40(&title[0])
+}
+
+// Titleの文字数の判定 (候補の文章にも使う)
+pub fn title_within_40(title: &str) -> bool {
     title.chars().count() <= 40
 }
 
@@ -20,3 +38,166 @@ pub fn is_ssl_enabled(file_path: &str) -> io::Result<bool> {
     let re = Regex::new(r#"https://"#).unwrap();
     Ok(re.is_match(&contents))
 }
+
+// テキストの文字数 (Scope::MainContentなら本文のみ)
+pub fn text_length(file_path: &str, scope: Scope) -> usize {
+    corpus::page_texts_in(file_path, scope)
+        .iter()
+        .map(|text| text.chars().count())
+        .sum()
+}
+
+// テキスト内のクエリの使用回数
+pub fn query_count(file_path: &str, query: &str, scope: Scope) -> usize {
+    corpus::page_texts_in(file_path, scope)
+        .iter()
+        .map(|text| text.matches(query).count())
+        .sum()
+}
+
+// アンカーテキストを除いたテキストの文字数
+pub fn non_anchor_text_length(file_path: &str) -> usize {
+    text_streams::split_streams(file_path)
+        .report("", "")
+        .body
+        .chars
+}
+
+// aタグ以外のテキスト内のクエリの使用回数
+pub fn non_anchor_query_count(file_path: &str, query: &str) -> usize {
+    text_streams::split_streams(file_path)
+        .report(query, "")
+        .body
+        .first_query
+}
+
+// 見出しを降順に設定しているか
+pub fn headings_in_order(file_path: &str) -> bool {
+    heading_outline::build_outline(file_path).is_valid()
+}
+
+// Descriptionの文字数が120文字以下か
+pub fn description120(file_path: &str) -> bool {
+    metadata::parse_metadata(file_path)
+        .description
+        .is_some_and(|description| description_within_120(&description))
+}
+
+// Descriptionの文字数の判定 (候補の文章にも使う)
+pub fn description_within_120(description: &str) -> bool {
+    metadata::full_width_length(description) <= metadata::DESCRIPTION_MAX
+}
+
+// Descriptionにクエリを含むか
+pub fn description_contains(file_path: &str, query: &str) -> bool {
+    metadata::parse_metadata(file_path)
+        .description
+        .is_some_and(|description| strfn::contains_word(query, &description))
+}
+
+// metaキーワードにクエリを含むか
+pub fn keywords_contains(file_path: &str, query: &str) -> bool {
+    metadata::parse_metadata(file_path)
+        .keywords
+        .iter()
+        .any(|keyword| keyword.contains(query))
+}
+
+// og_urlなどのmetaタグを設定しているか
+pub fn has_meta(file_path: &str, property: &str) -> bool {
+    metadata::parse_metadata(file_path)
+        .meta(property)
+        .is_some_and(|content| !content.is_empty())
+}
+
+// すべての画像のアスペクト比を適切に設定しているか (実寸が分かる画像のみ判定)
+pub fn images_aspect_ratio_ok(file_path: &str) -> bool {
+    images::analyze_images(file_path)
+        .iter()
+        .all(|image| image.aspect_ratio_ok().unwrap_or(true))
+}
+
+// すべての画像の解像度を適切に設定しているか (実寸が分かる画像のみ判定)
+pub fn images_resolution_ok(file_path: &str) -> bool {
+    images::analyze_images(file_path)
+        .iter()
+        .all(|image| image.resolution_ok().unwrap_or(true))
+}
+
+// リンク化されている画像にalt属性を設定しているか
+pub fn linked_images_have_alt(file_path: &str) -> bool {
+    images::analyze_images(file_path)
+        .iter()
+        .filter(|image| image.linked && image.tag == "img")
+        .all(|image| image.has_alt())
+}
+
+// キャプション付きの画像の数
+pub fn captioned_image_count(file_path: &str) -> usize {
+    images::analyze_images(file_path)
+        .iter()
+        .filter(|image| image.tag == "img" && image.has_caption())
+        .count()
+}
+
+// 画像の使用枚数
+pub fn image_count(file_path: &str) -> usize {
+    images::image_count(&images::analyze_images(file_path))
+}
+
+// 静的に見積もった表示速度のリスクが基準以下か
+pub fn performance_risk_ok(file_path: &str, max_score: f64) -> bool {
+    performance::analyze(file_path).risk_score <= max_score
+}
+
+// テキストが圧縮して配信されているか (ヘッダを保存していない場合は判定できないのでNone)
+pub fn text_compression_enabled(file_path: &str) -> Option<bool> {
+    compression::analyze(file_path).is_compressed()
+}
+
+pub fn buttons_have_names(file_path: &str) -> bool {
+    accessibility::audit(file_path).buttons_named()
+}
+
+// 内容の分かるテキストでリンクを設定しているか
+pub fn links_descriptive(file_path: &str) -> bool {
+    accessibility::audit(file_path).count(|issue| matches!(issue, A11yIssue::NonDescriptiveLink(_)))
+        == 0
+}
+
+pub fn tap_targets_ok(file_path: &str) -> bool {
+    accessibility::audit(file_path).count(|issue| matches!(issue, A11yIssue::SmallTapTarget { .. }))
+        == 0
+}
+
+pub fn viewport_ok(file_path: &str) -> bool {
+    mobile::analyze(file_path).viewport_ok()
+}
+
+pub fn mobile_friendly(file_path: &str) -> bool {
+    mobile::analyze(file_path).is_mobile_friendly()
+}
+
+// noindexや別URLへの正規化が無く、検索結果に出るページか
+pub fn indexable(file_path: &str) -> bool {
+    indexability::analyze(file_path).is_indexable()
+}
+
+// 本文の読みやすさ (建石の式)
+pub fn readability(file_path: &str) -> f64 {
+    text_quality::analyze(file_path).readability
+}
+
+// 著者か監修者を明記しているか
+pub fn has_author(file_path: &str) -> bool {
+    eeat::extract(file_path).has_author()
+}
+
+pub fn authoritative_citation_count(file_path: &str) -> usize {
+    eeat::extract(file_path).citations.len()
+}
+
+// 最終更新 (更新日がなければ公開日) からの経過日数
+pub fn days_since_update(file_path: &str) -> Option<i64> {
+    freshness::analyze(file_path).age_days(chrono::Local::now().date_naive())
+}
@@ -0,0 +1,379 @@
+use crate::mods::corpus::{self, Scope};
+use crate::mods::corpus_stats::{query_terms, CorpusStats, TfWeighting};
+use crate::mods::intent::{self, Intent};
+use crate::mods::main_content::{self, BlockKind};
+use crate::mods::measures_items;
+use crate::mods::metadata::{self, full_width_length, DESCRIPTION_MAX};
+use crate::mods::strfn;
+use crate::mods::text_quality;
+use std::collections::HashSet;
+
+// Titleの雛形 (名前, 雛形)
+// {main} {sub} {k1}～{k3} {year} {site} {h1} を置き換える
+const TITLE_TEMPLATES: [(&str, &str); 7] = [
+    ("what_is", "{main}の{sub}とは？{k1}と{k2}を解説"),
+    ("guide", "{main}の{sub}ガイド｜{k1}・{k2}のポイント"),
+    ("year", "【{year}年】{main}の{sub}｜{k1}をわかりやすく紹介"),
+    ("howto", "{main}で{sub}する方法｜{k1}と{k2}"),
+    ("list", "{main}の{sub}まとめ｜{k1}・{k2}・{k3}"),
+    ("site", "{main}の{sub}｜{site}"),
+    ("h1", "{main}｜{h1}"),
+];
+// Descriptionの書き出しの雛形
+const DESCRIPTION_TEMPLATES: [(&str, &str); 2] = [
+    (
+        "summary",
+        "{main}の{sub}について、{k1}や{k2}などのポイントを解説します。",
+    ),
+    (
+        "question",
+        "{main}の{sub}をお探しですか？{k1}と{k2}を中心に紹介します。",
+    ),
+];
+// 文の位置による重み (先頭ほど要約に向く)
+const POSITION_DECAY: f64 = 0.1;
+
+// 生成する候補の数や条件
+#[derive(Debug, Clone)]
+pub struct SuggestConfig {
+    pub keyphrases: usize,
+    pub max_titles: usize,
+    pub max_descriptions: usize,
+    pub title_early: f64,       // 第一クエリを置く位置の上限 (全角換算)
+    pub description_early: f64, // 同上
+    pub description_min: f64,   // 短すぎるDescriptionを避ける
+}
+
+impl Default for SuggestConfig {
+    fn default() -> Self {
+        SuggestConfig {
+            keyphrases: 5,
+            max_titles: 5,
+            max_descriptions: 3,
+            title_early: 10.0,
+            description_early: 30.0,
+            description_min: 60.0,
+        }
+    }
+}
+
+// TitleかDescriptionの候補
+#[derive(Debug, Clone, Default)]
+pub struct Suggestion {
+    pub text: String,
+    pub source: String, // 雛形の名前か "extractive" / "lead"
+    pub rules: Vec<(&'static str, bool)>,
+    pub score: f64, // この候補に差し替えた場合の予測スコア (100点満点)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Suggestions {
+    pub keyphrases: Vec<String>,
+    pub titles: Vec<Suggestion>,
+    pub descriptions: Vec<Suggestion>,
+}
+
+// 候補を作るための材料
+struct Inputs<'a> {
+    main_query: &'a str,
+    sub_query: &'a str,
+    keyphrases: &'a [String],
+    h1: String,
+    site_name: String,
+    year: i32,
+}
+
+impl Inputs<'_> {
+    // 置き換えられない値があればNone
+    fn fill(&self, template: &str) -> Option<String> {
+        let keyphrase = |index: usize| self.keyphrases.get(index).map(String::as_str);
+        let values = [
+            ("{main}", Some(self.main_query)),
+            ("{sub}", Some(self.sub_query)),
+            ("{k1}", keyphrase(0)),
+            ("{k2}", keyphrase(1)),
+            ("{k3}", keyphrase(2)),
+            ("{site}", Some(self.site_name.as_str())),
+            ("{h1}", Some(self.h1.as_str())),
+        ];
+        let mut text = template.replace("{year}", &self.year.to_string());
+        for (key, value) in values {
+            if !text.contains(key) {
+                continue;
+            }
+            match value {
+                Some(value) if !value.is_empty() => text = text.replace(key, value),
+                _ => return None,
+            }
+        }
+        Some(text)
+    }
+}
+
+// クエリが現れる位置 (全角換算)
+fn query_offset(text: &str, query: &str) -> Option<f64> {
+    text.find(query)
+        .map(|position| full_width_length(&text[..position]))
+}
+
+// 監査と同じ判定で候補を評価する (名前はseo.rsのチェック項目に対応)
+fn title_rules(title: &str, inputs: &Inputs) -> Vec<(&'static str, bool)> {
+    vec![
+        ("title40", measures_items::title_within_40(title)),
+        (
+            "title_first_query",
+            strfn::contains_word(inputs.main_query, title),
+        ),
+        (
+            "title_second_query",
+            strfn::contains_word(inputs.sub_query, title),
+        ),
+    ]
+}
+
+fn description_rules(description: &str, inputs: &Inputs) -> Vec<(&'static str, bool)> {
+    vec![
+        (
+            "description120",
+            measures_items::description_within_120(description),
+        ),
+        (
+            "description_first_query",
+            strfn::contains_word(inputs.main_query, description),
+        ),
+        (
+            "description_second_query",
+            strfn::contains_word(inputs.sub_query, description),
+        ),
+    ]
+}
+
+// 文字数の上限内で第一クエリを先頭近くに含む候補か
+fn title_acceptable(title: &str, inputs: &Inputs, config: &SuggestConfig) -> bool {
+    measures_items::title_within_40(title)
+        && query_offset(title, inputs.main_query).is_some_and(|offset| offset <= config.title_early)
+}
+
+fn description_acceptable(description: &str, inputs: &Inputs, config: &SuggestConfig) -> bool {
+    measures_items::description_within_120(description)
+        && full_width_length(description) >= config.description_min
+        && query_offset(description, inputs.main_query)
+            .is_some_and(|offset| offset <= config.description_early)
+}
+
+// 条件を満たす候補だけを残し、予測スコアの高い順に並べる
+fn rank(
+    candidates: Vec<(String, String)>,
+    rules: impl Fn(&str) -> Vec<(&'static str, bool)>,
+    acceptable: impl Fn(&str) -> bool,
+    base_results: &[(&str, bool)],
+    intent: Intent,
+    limit: usize,
+) -> Vec<Suggestion> {
+    let mut seen = HashSet::new();
+    let mut suggestions: Vec<Suggestion> = candidates
+        .into_iter()
+        .filter(|(text, _)| seen.insert(text.clone()) && acceptable(text))
+        .map(|(text, source)| {
+            let rules = rules(&text);
+            let results: Vec<(&str, bool)> = base_results
+                .iter()
+                .copied()
+                .chain(rules.iter().copied())
+                .collect();
+            Suggestion {
+                score: intent::weighted_score(&results, intent),
+                text,
+                source,
+                rules,
+            }
+        })
+        .collect();
+    // 同じスコアなら文字数の上限を有効に使っている方を優先する
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(full_width_length(&b.text).total_cmp(&full_width_length(&a.text)))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+// 上位ページと比べてページを特徴づける語 (クエリの語を除く)
+pub fn keyphrases(file_path: &str, main_query: &str, sub_query: &str, n: usize) -> Vec<String> {
+    let target = corpus::load_page_in(0, file_path, Scope::MainContent);
+    let mut pages = corpus::load_pages_in(corpus::TOP_RANKS, Scope::MainContent);
+    pages.push(target.clone());
+    let excluded: HashSet<String> = query_terms(main_query)
+        .into_iter()
+        .chain(query_terms(sub_query))
+        .collect();
+    CorpusStats::from_pages(&pages)
+        .tf_idf(&target, TfWeighting::Log)
+        .into_iter()
+        .map(|(term, _)| term)
+        .filter(|term| {
+            term.chars().count() >= 2
+                && !excluded.contains(term)
+                && !term.chars().all(|c| c.is_ascii_digit())
+        })
+        .take(n)
+        .collect()
+}
+
+// キーフレーズとクエリを多く含み、先頭に近い文ほど高くする
+fn sentence_scores(sentences: &[String], inputs: &Inputs) -> Vec<f64> {
+    sentences
+        .iter()
+        .enumerate()
+        .map(|(index, sentence)| {
+            let covered = inputs
+                .keyphrases
+                .iter()
+                .filter(|keyphrase| sentence.contains(keyphrase.as_str()))
+                .count() as f64;
+            let queries = [(inputs.main_query, 2.0), (inputs.sub_query, 1.0)]
+                .iter()
+                .filter(|(query, _)| sentence.contains(query))
+                .map(|(_, weight)| weight)
+                .sum::<f64>();
+            let length = sentence.chars().count().max(1) as f64;
+            (covered + queries) / length.sqrt() / (1.0 + POSITION_DECAY * index as f64)
+        })
+        .collect()
+}
+
+// 上限を超えない範囲で文を元の順番のまま足していく
+fn fill_sentences(prefix: &str, sentences: &[String], order: &[usize]) -> String {
+    let mut chosen: Vec<usize> = Vec::new();
+    for &index in order {
+        let mut candidate = chosen.clone();
+        candidate.push(index);
+        candidate.sort();
+        let text = candidate
+            .iter()
+            .map(|&i| sentences[i].as_str())
+            .collect::<String>();
+        if full_width_length(&format!("{}{}", prefix, text)) <= DESCRIPTION_MAX {
+            chosen = candidate;
+        }
+    }
+    let body: String = chosen.iter().map(|&i| sentences[i].as_str()).collect();
+    format!("{}{}", prefix, body)
+}
+
+// 第一クエリが先頭近くに無ければ見出しを付ける
+fn with_query_prefix(text: String, inputs: &Inputs, config: &SuggestConfig) -> String {
+    let early = query_offset(&text, inputs.main_query)
+        .is_some_and(|offset| offset <= config.description_early);
+    if early {
+        text
+    } else {
+        format!("【{}の{}】{}", inputs.main_query, inputs.sub_query, text)
+    }
+}
+
+fn description_candidates(
+    sentences: &[String],
+    inputs: &Inputs,
+    config: &SuggestConfig,
+) -> Vec<(String, String)> {
+    let scores = sentence_scores(sentences, inputs);
+    let mut ranked: Vec<usize> = (0..sentences.len()).collect();
+    ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    let lead: Vec<usize> = (0..sentences.len()).collect();
+
+    let mut candidates = Vec::new();
+    // 見出しの分を空けてから文を選ぶ
+    let reserved = format!("【{}の{}】", inputs.main_query, inputs.sub_query);
+    for (source, order) in [("extractive", &ranked), ("lead", &lead)] {
+        let text = fill_sentences(&reserved, sentences, order);
+        let text = text.trim_start_matches(&reserved).to_string();
+        if !text.is_empty() {
+            candidates.push((with_query_prefix(text, inputs, config), source.to_string()));
+        }
+    }
+    for (name, template) in DESCRIPTION_TEMPLATES {
+        if let Some(opening) = inputs.fill(template) {
+            candidates.push((
+                fill_sentences(&opening, sentences, &ranked),
+                name.to_string(),
+            ));
+        }
+    }
+    candidates
+}
+
+fn title_candidates(inputs: &Inputs) -> Vec<(String, String)> {
+    let mut candidates: Vec<(String, String)> = TITLE_TEMPLATES
+        .iter()
+        .filter_map(|(name, template)| Some((inputs.fill(template)?, name.to_string())))
+        .collect();
+    // h1が第一クエリで始まっていればそのまま使う
+    if inputs.h1.starts_with(inputs.main_query) {
+        candidates.push((inputs.h1.clone(), "h1".to_string()));
+    }
+    candidates
+}
+
+// 本文・キーフレーズ・クエリからTitleとDescriptionの候補を作る
+// base_resultsはTitle・Description以外の項目の結果で、予測スコアに含める
+pub fn suggest(
+    file_path: &str,
+    main_query: &str,
+    sub_query: &str,
+    intent: Intent,
+    base_results: &[(&str, bool)],
+    config: &SuggestConfig,
+) -> Suggestions {
+    let content = main_content::extract_main(file_path);
+    let metadata = metadata::parse_metadata(file_path);
+    let keyphrases = keyphrases(file_path, main_query, sub_query, config.keyphrases);
+    let h1 = content
+        .headings()
+        .into_iter()
+        .find(|(level, _)| *level == 1)
+        .map(|(_, text)| text)
+        .unwrap_or_default();
+    let inputs = Inputs {
+        main_query,
+        sub_query,
+        keyphrases: &keyphrases,
+        h1,
+        site_name: metadata.og.site_name.clone().unwrap_or_default(),
+        year: chrono::Datelike::year(&chrono::Local::now().date_naive()),
+    };
+
+    let paragraphs: Vec<String> = content
+        .blocks
+        .into_iter()
+        .filter(|block| block.kind == BlockKind::Text)
+        .map(|block| block.text)
+        .collect();
+    let sentences: Vec<String> = text_quality::split_sentences(&paragraphs.join("\n"))
+        .into_iter()
+        .filter(|sentence| full_width_length(sentence) < DESCRIPTION_MAX)
+        .collect();
+
+    let titles = rank(
+        title_candidates(&inputs),
+        |title| title_rules(title, &inputs),
+        |title| title_acceptable(title, &inputs, config),
+        base_results,
+        intent,
+        config.max_titles,
+    );
+    let descriptions = rank(
+        description_candidates(&sentences, &inputs, config),
+        |description| description_rules(description, &inputs),
+        |description| description_acceptable(description, &inputs, config),
+        base_results,
+        intent,
+        config.max_descriptions,
+    );
+    Suggestions {
+        keyphrases,
+        titles,
+        descriptions,
+    }
+}
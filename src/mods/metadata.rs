@@ -0,0 +1,323 @@
+use crate::mods::traits::Formatter;
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+
+// Descriptionの上限 (全角換算)
+pub const DESCRIPTION_MAX: f64 = 120.0;
+// Titleの上限 (全角換算)
+pub const TITLE_MAX: f64 = 40.0;
+
+// 必須とするOGPのプロパティ
+pub const REQUIRED_OG: [&str; 6] = [
+    "og:title",
+    "og:type",
+    "og:url",
+    "og:image",
+    "og:description",
+    "og:site_name",
+];
+const IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "gif", "webp", "avif"];
+// 複数指定できるOGPのプロパティ (前方一致)
+const REPEATABLE_PREFIXES: [&str; 5] = [
+    "og:image",
+    "og:video",
+    "og:audio",
+    "og:locale:alternate",
+    "article:tag",
+];
+
+// OGPのタグ
+#[derive(Debug, Clone, Default)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    pub og_type: Option<String>,
+    pub url: Option<String>,
+    pub image: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub locale: Option<String>,
+}
+
+// Twitterカードのタグ
+#[derive(Debug, Clone, Default)]
+pub struct TwitterCard {
+    pub card: Option<String>,
+    pub site: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+// <link rel>タグ
+#[derive(Debug, Clone, Default)]
+pub struct LinkTag {
+    pub rel: String,
+    pub href: String,
+    pub hreflang: Option<String>,
+    pub media: Option<String>,
+    pub link_type: Option<String>,
+}
+
+// ページのメタデータをまとめた構造体
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    pub charset: Option<String>,
+    pub viewport: Option<String>,
+    pub robots: Option<String>,
+    pub og: OpenGraph,
+    pub twitter: TwitterCard,
+    pub metas: Vec<(String, String)>, // name/property/http-equivとcontentの組
+    pub links: Vec<LinkTag>,
+    pub saved_from: Option<String>, // ブラウザで保存した際の元URL
+}
+
+// メタデータの問題点
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaIssue {
+    MissingTitle,
+    TitleTooLong(f64),
+    MissingDescription,
+    DescriptionTooLong(f64),
+    MissingProperty(String),
+    RelativeUrl { property: String, url: String },
+    InvalidImageUrl { property: String, url: String },
+    DuplicateMeta { key: String, count: usize },
+    DescriptionConflict,
+}
+
+// 全角を1文字、半角を0.5文字として数える
+pub fn full_width_length(text: &str) -> f64 {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii() || ('\u{FF61}'..='\u{FF9F}').contains(&c) {
+                0.5
+            } else {
+                1.0
+            }
+        })
+        .sum()
+}
+
+pub fn is_absolute_url(value: &str) -> bool {
+    url::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+// 画像として扱える拡張子のURLか
+pub fn is_image_url(value: &str) -> bool {
+    let Ok(url) = url::Url::parse(value) else {
+        return false;
+    };
+    // 最後のセグメントの拡張子だけを見る (/v1.2/photo のようなパスを誤判定しない)
+    let path = url.path().to_lowercase();
+    let segment = path.rsplit('/').next().unwrap_or("");
+    match segment.rsplit_once('.') {
+        Some((_, extension)) => IMAGE_EXTENSIONS.contains(&extension),
+        // 拡張子のない画像配信URLは許容する
+        None => true,
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.replace_ws().format_ws()
+}
+
+pub fn parse_metadata_html(html_content: &str) -> Metadata {
+    let document = Html::parse_document(html_content);
+    let title_selector = Selector::parse("title").unwrap();
+    let meta_selector = Selector::parse("meta").unwrap();
+    let link_selector = Selector::parse("link[rel]").unwrap();
+
+    let saved_from = Regex::new(r"<!-- saved from url=\(\d+\)(\S+) -->").unwrap();
+    let mut metadata = Metadata {
+        saved_from: saved_from
+            .captures(html_content)
+            .map(|captures| captures[1].to_string()),
+        title: document
+            .select(&title_selector)
+            .next()
+            .map(|title| normalize(&title.text().collect::<String>())),
+        ..Default::default()
+    };
+
+    for element in document.select(&meta_selector) {
+        let value = element.value();
+        if let Some(charset) = value.attr("charset") {
+            metadata.charset = Some(charset.to_string());
+        }
+        let Some(key) = value
+            .attr("name")
+            .or(value.attr("property"))
+            .or(value.attr("http-equiv"))
+        else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let content = normalize(value.attr("content").unwrap_or(""));
+        metadata.metas.push((key.clone(), content.clone()));
+
+        // 最初に出現したものを採用する
+        let slot = match key.as_str() {
+            "description" => &mut metadata.description,
+            "viewport" => &mut metadata.viewport,
+            "robots" => &mut metadata.robots,
+            "og:title" => &mut metadata.og.title,
+            "og:type" => &mut metadata.og.og_type,
+            "og:url" => &mut metadata.og.url,
+            "og:image" => &mut metadata.og.image,
+            "og:description" => &mut metadata.og.description,
+            "og:site_name" => &mut metadata.og.site_name,
+            "og:locale" => &mut metadata.og.locale,
+            "twitter:card" => &mut metadata.twitter.card,
+            "twitter:site" => &mut metadata.twitter.site,
+            "twitter:title" => &mut metadata.twitter.title,
+            "twitter:description" => &mut metadata.twitter.description,
+            "twitter:image" => &mut metadata.twitter.image,
+            "keywords" => {
+                if metadata.keywords.is_empty() {
+                    metadata.keywords = content
+                        .split([',', '、', '，'])
+                        .map(|keyword| keyword.trim().to_string())
+                        .filter(|keyword| !keyword.is_empty())
+                        .collect();
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if slot.is_none() {
+            *slot = Some(content);
+        }
+    }
+
+    for element in document.select(&link_selector) {
+        let value = element.value();
+        metadata.links.push(LinkTag {
+            rel: value.attr("rel").unwrap_or("").trim().to_lowercase(),
+            href: value.attr("href").unwrap_or("").trim().to_string(),
+            hreflang: value.attr("hreflang").map(str::to_string),
+            media: value.attr("media").map(str::to_string),
+            link_type: value.attr("type").map(str::to_string),
+        });
+    }
+    metadata
+}
+
+pub fn parse_metadata(file_path: &str) -> Metadata {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    parse_metadata_html(&html_content)
+}
+
+impl Metadata {
+    // name/propertyを指定してcontentを取り出す
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.metas
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, content)| content.as_str())
+    }
+
+    pub fn links_by_rel(&self, rel: &str) -> Vec<&LinkTag> {
+        self.links
+            .iter()
+            .filter(|link| link.rel.split_whitespace().any(|r| r == rel))
+            .collect()
+    }
+
+    // ページのURL (保存元、canonical、og:urlの順に探す)
+    pub fn page_url(&self) -> Option<url::Url> {
+        let canonical = self
+            .links_by_rel("canonical")
+            .first()
+            .map(|link| link.href.clone());
+        [self.saved_from.clone(), canonical, self.og.url.clone()]
+            .into_iter()
+            .flatten()
+            .find_map(|candidate| url::Url::parse(&candidate).ok())
+    }
+
+    pub fn validate(&self) -> Vec<MetaIssue> {
+        let mut issues = Vec::new();
+
+        match &self.title {
+            None => issues.push(MetaIssue::MissingTitle),
+            Some(title) if title.is_empty() => issues.push(MetaIssue::MissingTitle),
+            Some(title) if full_width_length(title) > TITLE_MAX => {
+                issues.push(MetaIssue::TitleTooLong(full_width_length(title)))
+            }
+            _ => {}
+        }
+        match &self.description {
+            None => issues.push(MetaIssue::MissingDescription),
+            Some(description) if description.is_empty() => {
+                issues.push(MetaIssue::MissingDescription)
+            }
+            Some(description) if full_width_length(description) > DESCRIPTION_MAX => issues.push(
+                MetaIssue::DescriptionTooLong(full_width_length(description)),
+            ),
+            _ => {}
+        }
+
+        for property in REQUIRED_OG {
+            if self.meta(property).is_none_or(|content| content.is_empty()) {
+                issues.push(MetaIssue::MissingProperty(property.to_string()));
+            }
+        }
+
+        // URLを値に取るプロパティは絶対URLであること
+        for property in ["og:url", "og:image", "twitter:image"] {
+            if let Some(url) = self.meta(property).filter(|url| !url.is_empty()) {
+                if !is_absolute_url(url) {
+                    issues.push(MetaIssue::RelativeUrl {
+                        property: property.to_string(),
+                        url: url.to_string(),
+                    });
+                } else if property != "og:url" && !is_image_url(url) {
+                    issues.push(MetaIssue::InvalidImageUrl {
+                        property: property.to_string(),
+                        url: url.to_string(),
+                    });
+                }
+            }
+        }
+
+        // 同じキーのmetaが複数ある
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (key, _) in &self.metas {
+            *counts.entry(key.as_str()).or_insert(0) += 1;
+        }
+        let mut duplicates: Vec<(&str, usize)> = counts
+            .into_iter()
+            .filter(|(key, count)| {
+                *count > 1
+                    && !REPEATABLE_PREFIXES
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+            })
+            .collect();
+        duplicates.sort();
+        for (key, count) in duplicates {
+            issues.push(MetaIssue::DuplicateMeta {
+                key: key.to_string(),
+                count,
+            });
+        }
+
+        if let (Some(description), Some(og_description)) = (&self.description, &self.og.description)
+        {
+            // 空白の違いや、片方がもう片方を省略したものは食い違いとしない
+            let trim = |text: &str| {
+                let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+                text.trim_end_matches(['…', '.', '。']).to_string()
+            };
+            let (description, og_description) = (trim(description), trim(og_description));
+            if !description.contains(&og_description) && !og_description.contains(&description) {
+                issues.push(MetaIssue::DescriptionConflict);
+            }
+        }
+        issues
+    }
+}
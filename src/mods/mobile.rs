@@ -0,0 +1,332 @@
+use crate::mods::accessibility::{parse_px, parse_style, selector_of};
+use crate::mods::dom_counter;
+use crate::mods::get_html::MediaTags;
+use crate::mods::images;
+use crate::mods::metadata;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+// スマートフォンの画面幅として扱う上限 (px)
+pub const MAX_FIXED_WIDTH: f64 = 480.0;
+// これより小さい文字は読みにくい (px)
+pub const MIN_FONT_SIZE: f64 = 12.0;
+// ズームを制限しているとみなす最大倍率
+const MIN_MAXIMUM_SCALE: f64 = 5.0;
+
+// viewportの設定値
+#[derive(Debug, Clone, Default)]
+pub struct Viewport {
+    pub width: Option<String>, // "device-width" または数値
+    pub initial_scale: Option<f64>,
+    pub user_scalable: Option<bool>,
+    pub maximum_scale: Option<f64>,
+}
+
+impl Viewport {
+    pub fn parse(content: &str) -> Self {
+        let mut viewport = Viewport::default();
+        for pair in content.split([',', ';']) {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().to_lowercase();
+            match key.trim().to_lowercase().as_str() {
+                "width" => viewport.width = Some(value),
+                "initial-scale" => viewport.initial_scale = value.parse().ok(),
+                "maximum-scale" => viewport.maximum_scale = value.parse().ok(),
+                "user-scalable" => {
+                    viewport.user_scalable = Some(!matches!(value.as_str(), "no" | "0"))
+                }
+                _ => {}
+            }
+        }
+        viewport
+    }
+
+    pub fn is_device_width(&self) -> bool {
+        self.width.as_deref() == Some("device-width")
+    }
+
+    // 拡大縮小を禁止しているか
+    pub fn blocks_zoom(&self) -> bool {
+        self.user_scalable == Some(false)
+            || self
+                .maximum_scale
+                .is_some_and(|scale| scale < MIN_MAXIMUM_SCALE)
+    }
+}
+
+// モバイル表示の問題点
+#[derive(Debug, Clone, PartialEq)]
+pub enum MobileIssue {
+    MissingViewport,
+    FixedViewportWidth(String),
+    MissingViewportWidth, // widthもinitial-scaleも無く、PC向けの幅で表示される
+    MissingInitialScale,
+    ZoomDisabled,
+    FixedWidth(f64),
+    SmallFont(f64),
+    WideTable(f64),
+    WideImage(f64),
+    Plugin,
+}
+
+// 指摘1つ分 (selectorは要素またはCSSのセレクタ)
+#[derive(Debug, Clone)]
+pub struct MobileFinding {
+    pub selector: String,
+    pub issue: MobileIssue,
+}
+
+#[derive(Debug, Default)]
+pub struct MobileReport {
+    pub viewport: Option<Viewport>,
+    pub findings: Vec<MobileFinding>,
+    pub media: MediaTags, // HtmlTagsのメディア関連のタグ数
+}
+
+// CSSのルール1つ分
+#[derive(Debug, Clone, Default)]
+pub struct CssRule {
+    pub selector: String,
+    pub media: Vec<String>, // 囲んでいる@mediaの条件
+    pub declarations: HashMap<String, String>,
+}
+
+impl CssRule {
+    // PC向けや印刷用の@media内のルールはモバイルでは適用されない
+    fn applies_to_mobile(&self) -> bool {
+        !self
+            .media
+            .iter()
+            .any(|media| media.contains("min-width") || media.contains("print"))
+    }
+}
+
+// @mediaの入れ子を追いながらルールを取り出す
+pub fn parse_css(css: &str) -> Vec<CssRule> {
+    let comment = Regex::new(r"(?s)/\*.*?\*/").unwrap();
+    let css = comment.replace_all(css, "");
+    let mut rules = Vec::new();
+    let mut preludes: Vec<String> = Vec::new();
+    let mut buffer = String::new();
+    for c in css.chars() {
+        match c {
+            '{' => {
+                preludes.push(buffer.trim().to_string());
+                buffer.clear();
+            }
+            '}' => {
+                if let Some(prelude) = preludes.pop() {
+                    if !prelude.starts_with('@') && !buffer.trim().is_empty() {
+                        rules.push(CssRule {
+                            selector: prelude,
+                            media: preludes
+                                .iter()
+                                .filter(|p| p.starts_with("@media"))
+                                .map(|p| p.to_lowercase())
+                                .collect(),
+                            declarations: parse_style(&buffer),
+                        });
+                    }
+                }
+                buffer.clear();
+            }
+            _ => buffer.push(c),
+        }
+    }
+    rules
+}
+
+// 埋め込みCSSと保存済みの外部CSS
+fn collect_css(document: &Html, html_path: &str) -> String {
+    let style = Selector::parse("style").unwrap();
+    let stylesheet = Selector::parse(r#"link[rel~="stylesheet"][href]"#).unwrap();
+    let embedded = document
+        .select(&style)
+        .map(|element| element.text().collect::<String>());
+    let linked = document.select(&stylesheet).filter_map(|element| {
+        let path = images::local_path(html_path, element.value().attr("href")?)?;
+        std::fs::read_to_string(path).ok()
+    });
+    embedded.chain(linked).collect::<Vec<String>>().join("\n")
+}
+
+// 幅と文字サイズの宣言を調べる
+fn check_declarations(
+    selector: &str,
+    declarations: &HashMap<String, String>,
+    findings: &mut Vec<MobileFinding>,
+) {
+    let mut push = |issue| {
+        findings.push(MobileFinding {
+            selector: selector.to_string(),
+            issue,
+        })
+    };
+    for property in ["width", "min-width"] {
+        if let Some(width) = declarations.get(property).and_then(|v| parse_px(v)) {
+            if width > MAX_FIXED_WIDTH {
+                push(MobileIssue::FixedWidth(width));
+            }
+        }
+    }
+    if let Some(size) = declarations.get("font-size").and_then(|v| parse_px(v)) {
+        if size > 0.0 && size < MIN_FONT_SIZE {
+            push(MobileIssue::SmallFont(size));
+        }
+    }
+}
+
+// width属性かstyle属性の幅 (px)
+fn element_width(element: &ElementRef) -> Option<f64> {
+    let style = parse_style(element.value().attr("style").unwrap_or(""));
+    style.get("width").and_then(|v| parse_px(v)).or(element
+        .value()
+        .attr("width")
+        .and_then(|v| v.trim().trim_end_matches("px").parse().ok()))
+}
+
+// 横スクロールできる要素に包まれているか
+fn in_scroll_container(element: &ElementRef) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|ancestor| {
+            let style = parse_style(ancestor.value().attr("style").unwrap_or(""));
+            ["overflow", "overflow-x"].iter().any(|property| {
+                style
+                    .get(*property)
+                    .is_some_and(|v| v == "auto" || v == "scroll")
+            })
+        })
+}
+
+// max-width: 100% などで縮むようにしているか
+fn shrinks(declarations: &HashMap<String, String>) -> bool {
+    declarations
+        .get("max-width")
+        .is_some_and(|v| v.ends_with('%') || v.ends_with("vw"))
+}
+
+pub fn analyze_html(html_content: &str, html_path: &str) -> MobileReport {
+    let document = Html::parse_document(html_content);
+    let mut report = MobileReport {
+        viewport: metadata::parse_metadata_html(html_content)
+            .viewport
+            .map(|content| Viewport::parse(&content)),
+        media: MediaTags::from_counts(&dom_counter::count_tags_html(html_content)),
+        ..Default::default()
+    };
+
+    let mut push = |selector: &str, issue| {
+        report.findings.push(MobileFinding {
+            selector: selector.to_string(),
+            issue,
+        })
+    };
+    match &report.viewport {
+        None => push("meta[name=viewport]", MobileIssue::MissingViewport),
+        Some(viewport) => {
+            match &viewport.width {
+                Some(width) if !viewport.is_device_width() => push(
+                    "meta[name=viewport]",
+                    MobileIssue::FixedViewportWidth(width.clone()),
+                ),
+                // initial-scaleがあれば画面幅に合わせて表示される
+                None if viewport.initial_scale.is_none() => {
+                    push("meta[name=viewport]", MobileIssue::MissingViewportWidth)
+                }
+                _ => {}
+            }
+            // widthも無い場合はMissingViewportWidthとして指摘済み
+            if viewport.width.is_some() && viewport.initial_scale.is_none() {
+                push("meta[name=viewport]", MobileIssue::MissingInitialScale);
+            }
+            if viewport.blocks_zoom() {
+                push("meta[name=viewport]", MobileIssue::ZoomDisabled);
+            }
+        }
+    }
+
+    let rules: Vec<CssRule> = parse_css(&collect_css(&document, html_path))
+        .into_iter()
+        .filter(CssRule::applies_to_mobile)
+        .collect();
+    for rule in &rules {
+        check_declarations(&rule.selector, &rule.declarations, &mut report.findings);
+    }
+    // imgにmax-widthを指定するルールがあれば画像は画面幅に収まる
+    let images_shrink = rules.iter().any(|rule| {
+        rule.selector
+            .split(',')
+            .any(|s| s.split_whitespace().last() == Some("img"))
+            && shrinks(&rule.declarations)
+    });
+
+    for element in document.tree.nodes().filter_map(ElementRef::wrap) {
+        let value = element.value();
+        if let Some(style) = value.attr("style") {
+            check_declarations(
+                &selector_of(&element),
+                &parse_style(style),
+                &mut report.findings,
+            );
+        }
+        let width = element_width(&element).filter(|width| *width > MAX_FIXED_WIDTH);
+        let inline_shrinks = shrinks(&parse_style(value.attr("style").unwrap_or("")));
+        match value.name() {
+            "table" => {
+                if let Some(width) = width {
+                    if !in_scroll_container(&element) {
+                        report.findings.push(MobileFinding {
+                            selector: selector_of(&element),
+                            issue: MobileIssue::WideTable(width),
+                        });
+                    }
+                }
+            }
+            "img" => {
+                if let Some(width) = width {
+                    if !images_shrink && !inline_shrinks {
+                        report.findings.push(MobileFinding {
+                            selector: selector_of(&element),
+                            issue: MobileIssue::WideImage(width),
+                        });
+                    }
+                }
+            }
+            "embed" | "object" => report.findings.push(MobileFinding {
+                selector: selector_of(&element),
+                issue: MobileIssue::Plugin,
+            }),
+            _ => {}
+        }
+    }
+    report
+}
+
+pub fn analyze(file_path: &str) -> MobileReport {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    analyze_html(&html_content, file_path)
+}
+
+impl MobileReport {
+    // viewportが画面幅に合わせて表示され、ズームを制限していないか
+    pub fn viewport_ok(&self) -> bool {
+        !self.findings.iter().any(|finding| {
+            matches!(
+                finding.issue,
+                MobileIssue::MissingViewport
+                    | MobileIssue::FixedViewportWidth(_)
+                    | MobileIssue::MissingViewportWidth
+                    | MobileIssue::ZoomDisabled
+            )
+        })
+    }
+
+    pub fn is_mobile_friendly(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
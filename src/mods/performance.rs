@@ -0,0 +1,236 @@
+use crate::mods::compression::gzip_size;
+use crate::mods::images;
+use crate::mods::metadata;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+
+// リスクスコアの重み
+const BLOCKING_CSS_WEIGHT: f64 = 4.0;
+const BLOCKING_JS_WEIGHT: f64 = 8.0;
+const NO_DIMENSION_WEIGHT: f64 = 2.0;
+const FONT_DISPLAY_WEIGHT: f64 = 5.0;
+const THIRD_PARTY_WEIGHT: f64 = 3.0;
+const HINT_BONUS: f64 = 2.0;
+// 転送量の目安 (gzip後のバイト数)
+const HTML_BUDGET: usize = 50 * 1024;
+const CSS_BUDGET: usize = 100 * 1024;
+const JS_BUDGET: usize = 300 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Css,
+    Js,
+}
+
+// 外部リソース1つ分の情報
+#[derive(Debug, Clone)]
+pub struct Resource {
+    pub kind: ResourceKind,
+    pub url: String,
+    pub render_blocking: bool,
+    pub third_party: bool,
+    pub bytes: Option<usize>, // 保存済みファイルがある場合のサイズ
+    pub gzip_bytes: Option<usize>,
+}
+
+// 静的に見積もったパフォーマンスの結果
+#[derive(Debug, Clone, Default)]
+pub struct PerformanceReport {
+    pub resources: Vec<Resource>,
+    pub html_bytes: usize,
+    pub html_gzip_bytes: usize,
+    pub css_bytes: usize,
+    pub css_gzip_bytes: usize,
+    pub js_bytes: usize,
+    pub js_gzip_bytes: usize,
+    pub render_blocking_css: usize,
+    pub render_blocking_js: usize,
+    pub images_without_dimensions: usize,
+    pub font_faces: usize,
+    pub font_faces_without_display: usize,
+    pub preload: usize,
+    pub preconnect: usize,
+    pub third_party_origins: Vec<String>,
+    pub risk_score: f64, // 0 (低リスク) ～ 100 (高リスク)
+}
+
+fn in_head(element: &ElementRef) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .any(|ancestor| ancestor.value().name() == "head")
+}
+
+// 印刷用などのメディアクエリが付いたCSSは描画をブロックしない
+fn blocks_render_css(element: &ElementRef) -> bool {
+    let media = element.value().attr("media").unwrap_or("all").trim();
+    in_head(element)
+        && element.value().attr("disabled").is_none()
+        && matches!(media, "" | "all" | "screen")
+}
+
+fn blocks_render_js(element: &ElementRef) -> bool {
+    let value = element.value();
+    in_head(element)
+        && value.attr("async").is_none()
+        && value.attr("defer").is_none()
+        && value.attr("type") != Some("module")
+}
+
+// ページと別のホストから読み込んでいるか
+fn origin_of(src: &str, page_host: Option<&str>) -> Option<String> {
+    let absolute = if src.starts_with("//") {
+        format!("https:{}", src)
+    } else {
+        src.to_string()
+    };
+    let url = url::Url::parse(&absolute).ok()?;
+    let host = url.host_str()?.to_string();
+    match page_host {
+        Some(page_host) if host == page_host || host.ends_with(&format!(".{}", page_host)) => None,
+        _ => Some(host),
+    }
+}
+
+// @font-faceの数と、font-displayの無いものの数
+fn font_faces(css: &str) -> (usize, usize) {
+    let font_face = Regex::new(r"(?s)@font-face\s*\{[^}]*\}").unwrap();
+    let blocks: Vec<&str> = font_face.find_iter(css).map(|m| m.as_str()).collect();
+    let without_display = blocks
+        .iter()
+        .filter(|block| !block.contains("font-display"))
+        .count();
+    (blocks.len(), without_display)
+}
+
+pub fn analyze_html(html_content: &str, html_path: &str) -> PerformanceReport {
+    let document = Html::parse_document(html_content);
+    let page_host = metadata::parse_metadata_html(html_content)
+        .page_url()
+        .and_then(|url| {
+            url.host_str()
+                .map(|host| host.trim_start_matches("www.").to_string())
+        });
+    let stylesheet = Selector::parse(r#"link[rel~="stylesheet"]"#).unwrap();
+    let script = Selector::parse("script[src]").unwrap();
+    let style = Selector::parse("style").unwrap();
+    let hint = Selector::parse("link[rel]").unwrap();
+
+    let mut report = PerformanceReport {
+        html_bytes: html_content.len(),
+        html_gzip_bytes: gzip_size(html_content.as_bytes()),
+        ..Default::default()
+    };
+
+    let mut css_texts: Vec<String> = document
+        .select(&style)
+        .map(|element| element.text().collect())
+        .collect();
+
+    let links = document
+        .select(&stylesheet)
+        .map(|element| (element, ResourceKind::Css, "href"));
+    let scripts = document
+        .select(&script)
+        .map(|element| (element, ResourceKind::Js, "src"));
+    for (element, kind, attr) in links.chain(scripts) {
+        let url = element.value().attr(attr).unwrap_or("").to_string();
+        let content = images::local_path(html_path, &url).and_then(|path| std::fs::read(path).ok());
+        if kind == ResourceKind::Css {
+            if let Some(content) = &content {
+                css_texts.push(String::from_utf8_lossy(content).to_string());
+            }
+        }
+        let origin = origin_of(&url, page_host.as_deref());
+        if let Some(origin) = &origin {
+            if kind == ResourceKind::Js && !report.third_party_origins.contains(origin) {
+                report.third_party_origins.push(origin.clone());
+            }
+        }
+        report.resources.push(Resource {
+            kind,
+            render_blocking: match kind {
+                ResourceKind::Css => blocks_render_css(&element),
+                ResourceKind::Js => blocks_render_js(&element),
+            },
+            third_party: origin.is_some(),
+            bytes: content.as_ref().map(|content| content.len()),
+            gzip_bytes: content.as_ref().map(|content| gzip_size(content)),
+            url,
+        });
+    }
+
+    for resource in &report.resources {
+        let (bytes, gzip_bytes, blocking) = match resource.kind {
+            ResourceKind::Css => (
+                &mut report.css_bytes,
+                &mut report.css_gzip_bytes,
+                &mut report.render_blocking_css,
+            ),
+            ResourceKind::Js => (
+                &mut report.js_bytes,
+                &mut report.js_gzip_bytes,
+                &mut report.render_blocking_js,
+            ),
+        };
+        *bytes += resource.bytes.unwrap_or(0);
+        *gzip_bytes += resource.gzip_bytes.unwrap_or(0);
+        if resource.render_blocking {
+            *blocking += 1;
+        }
+    }
+
+    for css in &css_texts {
+        let (faces, without_display) = font_faces(css);
+        report.font_faces += faces;
+        report.font_faces_without_display += without_display;
+    }
+
+    for element in document.select(&hint) {
+        let rel = element.value().attr("rel").unwrap_or("").to_lowercase();
+        if rel
+            .split_whitespace()
+            .any(|r| r == "preload" || r == "modulepreload")
+        {
+            report.preload += 1;
+        }
+        if rel
+            .split_whitespace()
+            .any(|r| r == "preconnect" || r == "dns-prefetch")
+        {
+            report.preconnect += 1;
+        }
+    }
+
+    // width/heightの無い画像はレイアウトシフトの原因になる
+    report.images_without_dimensions = images::analyze_images_html(html_content, html_path)
+        .iter()
+        .filter(|image| image.tag == "img" && !image.has_dimensions())
+        .count();
+
+    report.risk_score = risk_score(&report);
+    report
+}
+
+pub fn analyze(file_path: &str) -> PerformanceReport {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    analyze_html(&html_content, file_path)
+}
+
+// 目安を超えた分を100KBごとに1点とする
+fn over_budget(bytes: usize, budget: usize) -> f64 {
+    bytes.saturating_sub(budget) as f64 / (100.0 * 1024.0)
+}
+
+pub fn risk_score(report: &PerformanceReport) -> f64 {
+    let score = report.render_blocking_css as f64 * BLOCKING_CSS_WEIGHT
+        + report.render_blocking_js as f64 * BLOCKING_JS_WEIGHT
+        + report.images_without_dimensions.min(10) as f64 * NO_DIMENSION_WEIGHT
+        + report.font_faces_without_display as f64 * FONT_DISPLAY_WEIGHT
+        + report.third_party_origins.len() as f64 * THIRD_PARTY_WEIGHT
+        + over_budget(report.html_gzip_bytes, HTML_BUDGET) * 5.0
+        + over_budget(report.css_gzip_bytes, CSS_BUDGET) * 5.0
+        + over_budget(report.js_gzip_bytes, JS_BUDGET) * 5.0
+        - (report.preload + report.preconnect).min(5) as f64 * HINT_BONUS;
+    score.clamp(0.0, 100.0)
+}
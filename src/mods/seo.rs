@@ -1,13 +1,21 @@
+use mods::corpus::Scope;
 use mods::extract;
+use mods::intent;
 use mods::measures_items;
+use mods::meta_suggest;
+use mods::performance;
 use mods::strfn;
+use mods::summarize;
 
 mod mods;
 
 const FILE_PATH: &str = "./db/0.html";
 const FIRST_QUERY: &str = "北海道";
+const SECOND_QUERY: &str = "転職";
 
 fn main() {
+    //インデックスされないページは以降の項目を確認しても意味がない
+    println!("{}:インデックス可能", measures_items::indexable(FILE_PATH));
     //Titleとh1タグに異なる文字が含まれているかを確認する
     let title = extract::extract_title(FILE_PATH);
     let h1 = extract::extract_h1(FILE_PATH);
@@ -15,54 +23,204 @@ fn main() {
     println!("{}", h1);
     println!("{}", strfn::contains_word(&h1, &title));
     //リンクに説明文を付与
+    println!(
+        "{}:リンクに説明文を付与",
+        measures_items::links_descriptive(FILE_PATH)
+    );
 
     //Titleに第一クエリを設定
     let title = extract::extract_title(FILE_PATH);
     println!("{}", strfn::contains_word(FIRST_QUERY, &title));
     //ボタンと認識可能な名前を設定
+    println!(
+        "{}:ボタンと認識可能な名前を設定",
+        measures_items::buttons_have_names(FILE_PATH)
+    );
     //DOMサイズを1500いかに設定
     //すべての画像のアスペクト比を適切に設定
+    println!(
+        "{}:すべての画像のアスペクト比を適切に設定",
+        measures_items::images_aspect_ratio_ok(FILE_PATH)
+    );
     //HTMLファイルにDoctype宣言を設定
     //Titleに画像を設定しているか
     //SSLを設定
     //文字・画像が最初に表示される時間を４秒以下に設定
+    println!(
+        "{}:表示速度のリスクスコア",
+        performance::analyze(FILE_PATH).risk_score
+    );
     //見出しを降順に設定
+    println!(
+        "{}:見出しを降順に設定",
+        measures_items::headings_in_order(FILE_PATH)
+    );
     println!(
         "{}:Titleの文字数を40文字以下に設定",
         measures_items::title40()
     );
     //viewportの設定
+    println!("{}:viewportの設定", measures_items::viewport_ok(FILE_PATH));
+    println!(
+        "{}:モバイル表示の問題なし",
+        measures_items::mobile_friendly(FILE_PATH)
+    );
     //Titleに第二クエリを設定
     //タップターゲットの可動域
+    println!(
+        "{}:タップターゲットの可動域",
+        measures_items::tap_targets_ok(FILE_PATH)
+    );
     //Descriptionに第一クエリを設定
+    println!(
+        "{}:Descriptionに第一クエリを設定",
+        measures_items::description_contains(FILE_PATH, FIRST_QUERY)
+    );
     //metaタグに第一クエリを設定
     //metaタグにog_urlを設定
+    println!(
+        "{}:metaタグにog_urlを設定",
+        measures_items::has_meta(FILE_PATH, "og:url")
+    );
     //metaタグにog_typeを設定
+    println!(
+        "{}:metaタグにog_typeを設定",
+        measures_items::has_meta(FILE_PATH, "og:type")
+    );
     //metaタグにog_imageを設定
+    println!(
+        "{}:metaタグにog_imageを設定",
+        measures_items::has_meta(FILE_PATH, "og:image")
+    );
     //Descriptionの文字数を120文字以下に設定
+    println!(
+        "{}:Descriptionの文字数を120文字以下に設定",
+        measures_items::description120(FILE_PATH)
+    );
     //metaタグにog_descriptionを設定
+    println!(
+        "{}:metaタグにog_descriptionを設定",
+        measures_items::has_meta(FILE_PATH, "og:description")
+    );
     //metaタグにog_site_nameを設定
+    println!(
+        "{}:metaタグにog_site_nameを設定",
+        measures_items::has_meta(FILE_PATH, "og:site_name")
+    );
     //h1タグに第一クエリを設定
     //Descriptionに第二クエリを設定
     //すべての画像の解像度を適切に設定
+    println!(
+        "{}:すべての画像の解像度を適切に設定",
+        measures_items::images_resolution_ok(FILE_PATH)
+    );
     //Webフォントをロード中に代替テキストの設定
     //テキストの圧縮適応
+    println!(
+        "{:?}:テキストの圧縮適応",
+        measures_items::text_compression_enabled(FILE_PATH)
+    );
     //metaキーワードに第一クエリを設定
+    println!(
+        "{}:metaキーワードに第一クエリを設定",
+        measures_items::keywords_contains(FILE_PATH, FIRST_QUERY)
+    );
     //h1タグに第二クエリを設定
     //テキストの文字数を増加
+    println!(
+        "{}:本文の文字数",
+        measures_items::text_length(FILE_PATH, Scope::MainContent)
+    );
+    println!(
+        "{}:本文の読みやすさ",
+        measures_items::readability(FILE_PATH)
+    );
     //Webページの表示速度を６秒以下に
     //metaキーワードに第二クエリを設定
     //リンク化されている画像にalt属性を設定
+    println!(
+        "{}:リンク化されている画像にalt属性を設定",
+        measures_items::linked_images_have_alt(FILE_PATH)
+    );
     //画像の使用枚数を増加
+    println!(
+        "{}:画像の使用枚数",
+        measures_items::image_count(FILE_PATH)
+    );
     //アンカーテキストを排除したテキストの文字数を増加
+    println!(
+        "{}:アンカーテキストを除いた文字数",
+        measures_items::non_anchor_text_length(FILE_PATH)
+    );
     //テキスト内の第一クエリの使用回数を増加
+    println!(
+        "{}:本文内の第一クエリの使用回数",
+        measures_items::query_count(FILE_PATH, FIRST_QUERY, Scope::MainContent)
+    );
     //テキスト内の第二クエリの使用回数を増加
     //aタグ以外のテキスト内の第二クエリの使用回数を増加
     //aタグ以外のテキスト内の第一クエリの使用回数を増加
+    println!(
+        "{}:aタグ以外のテキスト内の第一クエリの使用回数",
+        measures_items::non_anchor_query_count(FILE_PATH, FIRST_QUERY)
+    );
     //主要なコンテンツの表示速度を４秒以下に設定
     //第一クエリを含むaltの個数を増加」
     //Webページが操作できるまでの時間を７秒以下に設定
     //第一クエリに含むstrongタグの使用回数を増加
     //画像に対してキャプションを設定
+    println!(
+        "{}:キャプション付きの画像の数",
+        measures_items::captioned_image_count(FILE_PATH)
+    );
     //第二クエリに含むstrongタグの使用回数を増加
+    //著者・監修者を明記
+    println!(
+        "{}:著者・監修者を明記",
+        measures_items::has_author(FILE_PATH)
+    );
+    //信頼できる情報源へのリンク
+    println!(
+        "{}:信頼できる情報源へのリンク数",
+        measures_items::authoritative_citation_count(FILE_PATH)
+    );
+    //情報の鮮度
+    println!(
+        "{:?}:最終更新からの経過日数",
+        measures_items::days_since_update(FILE_PATH)
+    );
+
+    //検索意図に応じて重み付けしたスコア
+    let intent = intent::classify(FIRST_QUERY, SECOND_QUERY);
+    let results = [
+        ("headings_in_order", measures_items::headings_in_order(FILE_PATH)),
+        ("performance", measures_items::performance_risk_ok(FILE_PATH, 30.0)),
+        ("mobile", measures_items::mobile_friendly(FILE_PATH)),
+        ("tap_targets", measures_items::tap_targets_ok(FILE_PATH)),
+        ("buttons_named", measures_items::buttons_have_names(FILE_PATH)),
+    ];
+    println!(
+        "{}:検索意図({:?})に応じたスコア",
+        intent::weighted_score(&results, intent),
+        intent
+    );
+
+    //TitleとDescriptionの候補
+    let suggestions = meta_suggest::suggest(
+        FILE_PATH,
+        FIRST_QUERY,
+        SECOND_QUERY,
+        intent,
+        &results,
+        &meta_suggest::SuggestConfig::default(),
+    );
+    for suggestion in suggestions.titles.iter().chain(&suggestions.descriptions) {
+        println!("{:.1}:{}", suggestion.score, suggestion.text);
+    }
+
+    //本文の要約
+    let summary = summarize::summarize(FILE_PATH, &summarize::SummaryConfig::default());
+    for line in summary.lines() {
+        println!("{}", line);
+    }
 }
@@ -0,0 +1,595 @@
+use crate::mods::accessibility;
+use crate::mods::corpus::{self, Page, Scope};
+use crate::mods::corpus_stats::{CorpusStats, TfWeighting};
+use crate::mods::headers;
+use crate::mods::indexability::normalize_url;
+use crate::mods::lazy_str::{unique_strings_with_count, VecToStr};
+use crate::mods::metadata;
+use crate::mods::structured_data::{self, PropValue, StructuredData};
+use crate::mods::traits::Formatter;
+use scraper::{Html, Selector};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+// クロールしたサイトの置き場所 (./site/0.html と、URLの一覧の ./site/urls.json)
+pub const SITE_DIR: &str = "./site";
+pub const URLS_FILE: &str = "urls.json";
+// パンくずリストを持つ要素
+const BREADCRUMB_SELECTOR: &str = "[class*=breadcrumb], [id*=breadcrumb], \
+    [aria-label*=readcrumb], [aria-label*=パンくず], [class*=topicpath], [id*=topicpath]";
+// サイトの多くのページがパンくずリストを持つとみなす割合
+const BREADCRUMB_MAJORITY: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct SiteConfig {
+    pub min_inlinks: usize,       // これより少ない内部被リンクのページを報告する
+    pub max_depth: usize,         // これより深いクリック数のページを報告する
+    pub key_terms: usize,         // ページごとに使う特徴語の数
+    pub min_matched_terms: usize, // リンクを提案する共通の特徴語の数
+    pub max_suggestions: usize,
+}
+
+impl Default for SiteConfig {
+    fn default() -> Self {
+        SiteConfig {
+            min_inlinks: 3,
+            max_depth: 3,
+            key_terms: 5,
+            min_matched_terms: 2,
+            max_suggestions: 20,
+        }
+    }
+}
+
+// 同じサイト内へのリンク
+#[derive(Debug, Clone, Default)]
+pub struct InternalLink {
+    pub target: String, // 正規化したURL
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SitePage {
+    pub url: String, // 正規化したURL
+    pub path: String,
+    pub links: Vec<InternalLink>,
+    pub breadcrumbs: Vec<String>, // パンくずリストのURL (上の階層から順に)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Site {
+    pub home: String,
+    pub pages: Vec<SitePage>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PageStats {
+    pub url: String,
+    pub depth: Option<usize>, // トップページからのクリック数 (たどれなければNone)
+    pub inlinks: usize,       // リンク元のページ数 (自身を除く)
+    pub outlinks: usize,
+}
+
+// リンク先ごとのアンカーテキストのばらつき
+#[derive(Debug, Clone, Default)]
+pub struct AnchorProfile {
+    pub target: String,
+    pub total: usize,
+    pub unique: usize,
+    pub diversity: f64, // 異なり数 / 延べ数
+    pub anchors: Vec<(String, i32)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BreadcrumbIssue {
+    Missing,                 // 他の多くのページにはある
+    BrokenCrumb(String),     // サイト内に無いURL
+    ParentNotLinked(String), // 1つ上の階層のページからリンクされていない
+}
+
+#[derive(Debug, Clone)]
+pub struct BreadcrumbFinding {
+    pub url: String,
+    pub issue: BreadcrumbIssue,
+}
+
+// 特徴語を含むのにリンクしていないページからのリンクの提案
+#[derive(Debug, Clone, Default)]
+pub struct LinkSuggestion {
+    pub source: String,
+    pub target: String,
+    pub terms: Vec<String>,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SiteReport {
+    pub home: String,
+    pub pages: Vec<PageStats>,
+    pub orphans: Vec<String>,     // どのページからもリンクされていない
+    pub unreachable: Vec<String>, // トップページからたどれない
+    pub weak: Vec<String>,        // 内部被リンクが少ない
+    pub deep: Vec<String>,        // クリック数が多い
+    pub anchors: Vec<AnchorProfile>,
+    pub breadcrumbs: Vec<BreadcrumbFinding>,
+    pub suggestions: Vec<LinkSuggestion>,
+}
+
+fn normalize(text: &str) -> String {
+    text.replace_ws().format_ws()
+}
+
+fn same_site(url: &url::Url, base: &url::Url) -> bool {
+    let host = |url: &url::Url| {
+        url.host_str()
+            .unwrap_or("")
+            .trim_start_matches("www.")
+            .to_string()
+    };
+    matches!(url.scheme(), "http" | "https") && host(url) == host(base)
+}
+
+// ページ内の同じサイトへのリンクとアンカーテキスト
+pub fn internal_links_html(html_content: &str, page_url: &url::Url) -> Vec<InternalLink> {
+    let document = Html::parse_document(html_content);
+    let link_selector = Selector::parse("a[href]").unwrap();
+    document
+        .select(&link_selector)
+        .filter_map(|link| {
+            let href = link.value().attr("href")?;
+            let target = page_url.join(href.trim()).ok()?;
+            same_site(&target, page_url).then(|| InternalLink {
+                target: normalize_url(&target),
+                anchor: normalize(&accessibility::content_text(&link)),
+            })
+        })
+        .collect()
+}
+
+// BreadcrumbListの各項目のURL (位置の順)
+fn structured_breadcrumbs(data: &StructuredData) -> Vec<String> {
+    let text = |value: &PropValue| match value {
+        PropValue::Text(text) => Some(text.clone()),
+        PropValue::Entity(index) => {
+            let entity = &data.entities[*index];
+            entity
+                .id
+                .clone()
+                .or_else(|| match entity.properties.get("url")?.first()? {
+                    PropValue::Text(url) => Some(url.clone()),
+                    PropValue::Entity(_) => None,
+                })
+        }
+    };
+    let Some(list) = data
+        .entities
+        .iter()
+        .find(|entity| entity.types.iter().any(|t| t == "BreadcrumbList"))
+    else {
+        return Vec::new();
+    };
+    let mut items: Vec<(usize, String)> = list
+        .properties
+        .get("itemListElement")
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let PropValue::Entity(index) = item else {
+                return None;
+            };
+            let properties = &data.entities[*index].properties;
+            let url = text(properties.get("item")?.first()?)?;
+            let position = properties
+                .get("position")
+                .and_then(|values| values.first())
+                .and_then(|value| match value {
+                    PropValue::Text(position) => position.parse().ok(),
+                    PropValue::Entity(_) => None,
+                })
+                .unwrap_or(usize::MAX);
+            Some((position, url))
+        })
+        .collect();
+    items.sort_by_key(|(position, _)| *position);
+    items.into_iter().map(|(_, url)| url).collect()
+}
+
+// パンくずリストのURL (構造化データが無ければHTMLから探す)
+pub fn breadcrumbs_html(html_content: &str, page_url: &url::Url) -> Vec<String> {
+    let mut urls = structured_breadcrumbs(&structured_data::extract_html(html_content));
+    if urls.is_empty() {
+        let document = Html::parse_document(html_content);
+        let container_selector = Selector::parse(BREADCRUMB_SELECTOR).unwrap();
+        let link_selector = Selector::parse("a[href]").unwrap();
+        if let Some(container) = document.select(&container_selector).next() {
+            urls = container
+                .select(&link_selector)
+                .filter_map(|link| link.value().attr("href").map(str::to_string))
+                .collect();
+        }
+    }
+    urls.into_iter()
+        .filter_map(|href| page_url.join(href.trim()).ok())
+        .map(|url| normalize_url(&url))
+        .collect()
+}
+
+fn load_page(html_path: &str, url: &url::Url) -> SitePage {
+    let html_content = std::fs::read_to_string(html_path).expect("Failed to read file");
+    SitePage {
+        url: normalize_url(url),
+        path: html_path.to_string(),
+        links: internal_links_html(&html_content, url),
+        breadcrumbs: breadcrumbs_html(&html_content, url),
+    }
+}
+
+// 保存済みのサイトを読み込む
+// urls.jsonがあればn番目のURLをn.htmlとし、無ければ各ページの保存元のURLを使う
+pub fn load_site(dir: &str) -> Site {
+    let urls_path = Path::new(dir).join(URLS_FILE);
+    let mut pages_by_url: Vec<(url::Url, String)> = Vec::new();
+    if let Ok(content) = std::fs::read_to_string(&urls_path) {
+        let urls: Vec<String> = serde_json::from_str(&content).expect("URLの一覧の形式が不正です");
+        for (index, url) in urls.iter().enumerate() {
+            let path = format!("{}/{}.html", dir, index);
+            if let (Ok(url), true) = (url::Url::parse(url), Path::new(&path).exists()) {
+                pages_by_url.push((url, path));
+            }
+        }
+    } else {
+        let mut paths: Vec<String> = std::fs::read_dir(dir)
+            .expect("サイトのディレクトリを読み込めませんでした")
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().to_string_lossy().to_string())
+            .filter(|path| path.ends_with(".html"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            if let Some(url) = metadata::parse_metadata(&path).page_url() {
+                pages_by_url.push((url, path));
+            }
+        }
+    }
+
+    let pages: Vec<SitePage> = pages_by_url
+        .iter()
+        .map(|(url, path)| load_page(path, url))
+        .collect();
+    // パスが "/" のページをトップページとし、無ければ最初のページにする
+    let home = pages_by_url
+        .iter()
+        .find(|(url, _)| url.path() == "/")
+        .or(pages_by_url.first())
+        .map(|(url, _)| normalize_url(url))
+        .unwrap_or_default();
+    Site { home, pages }
+}
+
+// トップページから幅優先でたどったクリック数
+pub fn click_depths(site: &Site) -> HashMap<String, usize> {
+    let links: HashMap<&str, &SitePage> = site
+        .pages
+        .iter()
+        .map(|page| (page.url.as_str(), page))
+        .collect();
+    let mut depths: HashMap<String, usize> = HashMap::new();
+    let mut queue = VecDeque::new();
+    if links.contains_key(site.home.as_str()) {
+        depths.insert(site.home.clone(), 0);
+        queue.push_back(site.home.clone());
+    }
+    while let Some(url) = queue.pop_front() {
+        let depth = depths[&url];
+        for link in &links[url.as_str()].links {
+            if links.contains_key(link.target.as_str()) && !depths.contains_key(&link.target) {
+                depths.insert(link.target.clone(), depth + 1);
+                queue.push_back(link.target.clone());
+            }
+        }
+    }
+    depths
+}
+
+// ページごとのリンク元のページ
+fn inlink_sources(site: &Site) -> HashMap<&str, HashSet<&str>> {
+    let mut sources: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for page in &site.pages {
+        for link in &page.links {
+            if link.target != page.url {
+                sources
+                    .entry(link.target.as_str())
+                    .or_default()
+                    .insert(page.url.as_str());
+            }
+        }
+    }
+    sources
+}
+
+pub fn anchor_profiles(site: &Site) -> Vec<AnchorProfile> {
+    let known: HashSet<&str> = site.pages.iter().map(|page| page.url.as_str()).collect();
+    let mut anchors: HashMap<&str, Vec<String>> = HashMap::new();
+    for page in &site.pages {
+        for link in &page.links {
+            if known.contains(link.target.as_str()) && link.target != page.url {
+                anchors
+                    .entry(link.target.as_str())
+                    .or_default()
+                    .push(link.anchor.clone());
+            }
+        }
+    }
+    let mut profiles: Vec<AnchorProfile> = anchors
+        .into_iter()
+        .map(|(target, texts)| {
+            let counts: Vec<(String, i32)> = unique_strings_with_count(texts.vec_to_str(), 1)
+                .into_iter()
+                .map(|(text, count)| (text.to_string(), count))
+                .collect();
+            AnchorProfile {
+                target: target.to_string(),
+                total: texts.len(),
+                unique: counts.len(),
+                diversity: counts.len() as f64 / texts.len() as f64,
+                anchors: counts,
+            }
+        })
+        .collect();
+    profiles.sort_by(|a, b| b.total.cmp(&a.total).then(a.target.cmp(&b.target)));
+    profiles
+}
+
+pub fn breadcrumb_findings(site: &Site) -> Vec<BreadcrumbFinding> {
+    let pages: HashMap<&str, &SitePage> = site
+        .pages
+        .iter()
+        .map(|page| (page.url.as_str(), page))
+        .collect();
+    let with_breadcrumbs = site
+        .pages
+        .iter()
+        .filter(|page| !page.breadcrumbs.is_empty())
+        .count();
+    let majority = with_breadcrumbs as f64 >= site.pages.len() as f64 * BREADCRUMB_MAJORITY;
+
+    let mut findings = Vec::new();
+    for page in &site.pages {
+        let finding = |issue| BreadcrumbFinding {
+            url: page.url.clone(),
+            issue,
+        };
+        if page.breadcrumbs.is_empty() {
+            if majority && with_breadcrumbs > 0 && page.url != site.home {
+                findings.push(finding(BreadcrumbIssue::Missing));
+            }
+            continue;
+        }
+        for crumb in &page.breadcrumbs {
+            if !pages.contains_key(crumb.as_str()) {
+                findings.push(finding(BreadcrumbIssue::BrokenCrumb(crumb.clone())));
+            }
+        }
+        // 最後の項目が自身なら、その1つ前が親
+        let ancestors: Vec<&String> = page
+            .breadcrumbs
+            .iter()
+            .filter(|crumb| **crumb != page.url)
+            .collect();
+        if let Some(parent) = ancestors.last().and_then(|url| pages.get(url.as_str())) {
+            if !parent.links.iter().any(|link| link.target == page.url) {
+                findings.push(finding(BreadcrumbIssue::ParentNotLinked(
+                    parent.url.clone(),
+                )));
+            }
+        }
+    }
+    findings
+}
+
+// リンク先の特徴語を本文に含むが、リンクしていないページを探す
+pub fn link_suggestions(site: &Site, config: &SiteConfig) -> Vec<LinkSuggestion> {
+    let corpus_pages: Vec<Page> = site
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| corpus::load_page_in(index, &page.path, Scope::MainContent))
+        .collect();
+    let stats = CorpusStats::from_pages(&corpus_pages);
+    let tokens: Vec<HashSet<String>> = corpus_pages
+        .iter()
+        .map(|page| page.tokens().into_iter().collect())
+        .collect();
+    let inlinks = inlink_sources(site);
+
+    let mut suggestions = Vec::new();
+    for (target_index, target) in site.pages.iter().enumerate() {
+        let key_terms: Vec<(String, f64)> = stats
+            .tf_idf(&corpus_pages[target_index], TfWeighting::Log)
+            .into_iter()
+            .filter(|(term, _)| term.chars().count() >= 2)
+            .take(config.key_terms)
+            .collect();
+        let linked_from = inlinks.get(target.url.as_str());
+        for (source_index, source) in site.pages.iter().enumerate() {
+            if source_index == target_index
+                || linked_from.is_some_and(|sources| sources.contains(source.url.as_str()))
+            {
+                continue;
+            }
+            let matched: Vec<&(String, f64)> = key_terms
+                .iter()
+                .filter(|(term, _)| tokens[source_index].contains(term))
+                .collect();
+            if matched.len() < config.min_matched_terms {
+                continue;
+            }
+            // 被リンクが少ないページへの提案を優先する
+            let weight: f64 = matched.iter().map(|(_, weight)| weight).sum();
+            let inlink_count = linked_from.map_or(0, HashSet::len);
+            suggestions.push(LinkSuggestion {
+                source: source.url.clone(),
+                target: target.url.clone(),
+                terms: matched.iter().map(|(term, _)| term.clone()).collect(),
+                score: weight / (1.0 + inlink_count as f64),
+            });
+        }
+    }
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    suggestions.truncate(config.max_suggestions);
+    suggestions
+}
+
+pub fn analyze(site: &Site, config: &SiteConfig) -> SiteReport {
+    let depths = click_depths(site);
+    let inlinks = inlink_sources(site);
+    let pages: Vec<PageStats> = site
+        .pages
+        .iter()
+        .map(|page| PageStats {
+            url: page.url.clone(),
+            depth: depths.get(&page.url).copied(),
+            inlinks: inlinks.get(page.url.as_str()).map_or(0, HashSet::len),
+            outlinks: page.links.len(),
+        })
+        .collect();
+    let urls_where = |condition: &dyn Fn(&PageStats) -> bool| -> Vec<String> {
+        pages
+            .iter()
+            .filter(|stats| condition(stats))
+            .map(|stats| stats.url.clone())
+            .collect()
+    };
+
+    SiteReport {
+        home: site.home.clone(),
+        orphans: urls_where(&|stats| stats.inlinks == 0 && stats.url != site.home),
+        unreachable: urls_where(&|stats| stats.depth.is_none()),
+        weak: urls_where(&|stats| stats.inlinks < config.min_inlinks && stats.url != site.home),
+        deep: urls_where(&|stats| stats.depth.is_some_and(|depth| depth > config.max_depth)),
+        anchors: anchor_profiles(site),
+        breadcrumbs: breadcrumb_findings(site),
+        suggestions: link_suggestions(site, config),
+        pages,
+    }
+}
+
+pub fn analyze_dir(dir: &str) -> SiteReport {
+    analyze(&load_site(dir), &SiteConfig::default())
+}
+
+// トップページから同じサイト内のリンクをたどって保存する
+// 取得できなかったURL (エラーのステータスを含む) は飛ばし、その一覧を返す
+pub async fn crawl(start_url: &str, dir: &str, max_pages: usize) -> Vec<String> {
+    let start = url::Url::parse(start_url).expect("URLの形式が不正です");
+    std::fs::create_dir_all(dir).expect("ディレクトリを作成できませんでした");
+    let mut urls: Vec<String> = Vec::new();
+    let mut failed: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut saved: HashSet<String> = HashSet::new();
+    let mut queue = VecDeque::from([start.clone()]);
+    seen.insert(normalize_url(&start));
+
+    while let Some(url) = queue.pop_front() {
+        if urls.len() >= max_pages {
+            break;
+        }
+        // 通信エラーや4xx/5xxのページは保存もリンクの追跡もしない
+        let Some(fetched) = headers::fetch(url.as_str())
+            .await
+            .ok()
+            .filter(|fetched| fetched.is_success())
+        else {
+            failed.push(url.to_string());
+            continue;
+        };
+        // PDFや画像などは保存しない
+        if !fetched.is_html() {
+            continue;
+        }
+        // リダイレクト後のURLで記録する
+        let final_url = url::Url::parse(&fetched.url).unwrap_or(url);
+        // 別サイトへのリダイレクトや、保存済みのページへのリダイレクトは飛ばす
+        if !same_site(&final_url, &start) || !saved.insert(normalize_url(&final_url)) {
+            continue;
+        }
+        seen.insert(normalize_url(&final_url));
+        fetched.save(&format!("{}/{}.html", dir, urls.len()));
+        urls.push(final_url.to_string());
+
+        let html_content = String::from_utf8_lossy(&fetched.body);
+        let document = Html::parse_document(&html_content);
+        let link_selector = Selector::parse("a[href]").unwrap();
+        for link in document.select(&link_selector) {
+            let Some(mut target) = link
+                .value()
+                .attr("href")
+                .and_then(|href| final_url.join(href.trim()).ok())
+            else {
+                continue;
+            };
+            target.set_fragment(None);
+            if same_site(&target, &start) && seen.insert(normalize_url(&target)) {
+                queue.push_back(target);
+            }
+        }
+    }
+    let content = serde_json::to_string_pretty(&urls).expect("JSONに変換できませんでした");
+    std::fs::write(Path::new(dir).join(URLS_FILE), content)
+        .expect("URLの一覧を保存できませんでした");
+    failed
+}
+
+impl SiteReport {
+    pub fn to_markdown(&self) -> String {
+        let mut result = format!(
+            "# サイト構造: {}\n\n{}ページ\n",
+            self.home,
+            self.pages.len()
+        );
+        let list = |title: &str, urls: &[String]| {
+            let mut section = format!("\n## {}\n\n", title);
+            for url in urls {
+                section.push_str(&format!("- {}\n", url));
+            }
+            section
+        };
+        result.push_str(&list("孤立ページ", &self.orphans));
+        result.push_str(&list("トップページからたどれないページ", &self.unreachable));
+        result.push_str(&list("内部被リンクが少ないページ", &self.weak));
+        result.push_str(&list("クリック数が多いページ", &self.deep));
+
+        result.push_str("\n## アンカーテキスト\n\n");
+        for profile in &self.anchors {
+            let anchors: Vec<String> = profile
+                .anchors
+                .iter()
+                .take(3)
+                .map(|(text, count)| format!("{}({})", text, count))
+                .collect();
+            result.push_str(&format!(
+                "- {} 延べ{} 異なり{} 例: {}\n",
+                profile.target,
+                profile.total,
+                profile.unique,
+                anchors.join(" / ")
+            ));
+        }
+
+        result.push_str("\n## パンくずリスト\n\n");
+        for finding in &self.breadcrumbs {
+            result.push_str(&format!("- {} {:?}\n", finding.url, finding.issue));
+        }
+
+        result.push_str("\n## 内部リンクの提案\n\n");
+        for suggestion in &self.suggestions {
+            result.push_str(&format!(
+                "- {} → {} ({})\n",
+                suggestion.source,
+                suggestion.target,
+                suggestion.terms.join("・")
+            ));
+        }
+        result
+    }
+}
@@ -9,3 +9,126 @@ pub fn word_distribution(strings: Vec<String>) -> Vec<(String, usize)> {
     result.sort_by(|a, b| b.1.cmp(&a.1));
     result
 }
+
+// 中央値 (空なら0)
+pub fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+// 標準正規分布の累積分布関数 (Abramowitz-Stegunの近似)
+pub fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+// 両側検定のp値
+pub fn two_sided_p(z: f64) -> f64 {
+    (2.0 * (1.0 - normal_cdf(z.abs()))).min(1.0)
+}
+
+// Mann-WhitneyのU検定の結果
+#[derive(Debug, Clone, Default)]
+pub struct MannWhitney {
+    pub u: f64,
+    pub z: f64,
+    pub p_value: f64,
+    pub effect_size: f64, // 順位双列相関 (正ならaの方が大きい)
+}
+
+// 同順位を平均順位として扱い、正規近似でp値を求める
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> MannWhitney {
+    let n1 = a.len() as f64;
+    let n2 = b.len() as f64;
+    if a.is_empty() || b.is_empty() {
+        return MannWhitney {
+            p_value: 1.0,
+            ..Default::default()
+        };
+    }
+
+    let mut values: Vec<(f64, bool)> = a
+        .iter()
+        .map(|&v| (v, true))
+        .chain(b.iter().map(|&v| (v, false)))
+        .collect();
+    values.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j + 1 < values.len() && values[j + 1].0 == values[i].0 {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let ties = (j - i + 1) as f64;
+        tie_term += ties.powi(3) - ties;
+        rank_sum_a += values[i..=j].iter().filter(|v| v.1).count() as f64 * rank;
+        i = j + 1;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let n = n1 + n2;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)).max(1.0));
+    let z = if variance > 0.0 {
+        (u - mean) / variance.sqrt()
+    } else {
+        0.0
+    };
+    MannWhitney {
+        u,
+        z,
+        p_value: two_sided_p(z),
+        effect_size: 2.0 * u / (n1 * n2) - 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_odd_and_even() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+    }
+
+    // 完全に分かれた3件ずつ: U=0, z=-4.5/√5.25
+    #[test]
+    fn mann_whitney_u_separated() {
+        let result = mann_whitney_u(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]);
+        assert_eq!(result.u, 0.0);
+        assert!((result.z - (-4.5 / 5.25_f64.sqrt())).abs() < 1e-9);
+        assert!((result.p_value - 0.0495).abs() < 1e-3);
+        assert_eq!(result.effect_size, -1.0);
+    }
+
+    #[test]
+    fn mann_whitney_u_identical() {
+        let result = mann_whitney_u(&[1.0, 1.0], &[1.0, 1.0]);
+        assert_eq!(result.u, 2.0);
+        assert_eq!(result.effect_size, 0.0);
+    }
+}
@@ -0,0 +1,463 @@
+use crate::mods::traits::Formatter;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+// 構造化データの記述形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    JsonLd,
+    Microdata,
+    Rdfa,
+}
+
+// プロパティの値 (文字列か、別のエンティティへの参照)
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropValue {
+    Text(String),
+    Entity(usize),
+}
+
+// 正規化したエンティティ
+#[derive(Debug, Clone)]
+pub struct Entity {
+    pub id: Option<String>,
+    pub types: Vec<String>,
+    pub properties: BTreeMap<String, Vec<PropValue>>,
+    pub syntax: Syntax,
+    pub nested: bool, // 他のエンティティのプロパティとして出現したか
+}
+
+// ページ内の全エンティティ
+#[derive(Debug, Clone, Default)]
+pub struct StructuredData {
+    pub entities: Vec<Entity>,
+}
+
+// schema.orgの型ごとの必須・推奨プロパティ
+pub struct TypeRule {
+    pub name: &'static str,
+    pub subtypes: &'static [&'static str],
+    pub required: &'static [&'static str],
+    pub recommended: &'static [&'static str],
+}
+
+pub const TYPE_RULES: [TypeRule; 6] = [
+    TypeRule {
+        name: "Article",
+        subtypes: &["NewsArticle", "BlogPosting", "TechArticle", "Report"],
+        required: &["headline"],
+        recommended: &[
+            "author",
+            "datePublished",
+            "dateModified",
+            "image",
+            "publisher",
+        ],
+    },
+    TypeRule {
+        name: "BreadcrumbList",
+        subtypes: &[],
+        required: &["itemListElement"],
+        recommended: &[],
+    },
+    TypeRule {
+        name: "FAQPage",
+        subtypes: &[],
+        required: &["mainEntity"],
+        recommended: &[],
+    },
+    TypeRule {
+        name: "JobPosting",
+        subtypes: &[],
+        required: &[
+            "title",
+            "description",
+            "datePosted",
+            "hiringOrganization",
+            "jobLocation",
+        ],
+        recommended: &[
+            "baseSalary",
+            "employmentType",
+            "validThrough",
+            "identifier",
+            "directApply",
+        ],
+    },
+    TypeRule {
+        name: "Organization",
+        subtypes: &["Corporation", "LocalBusiness", "NewsMediaOrganization"],
+        required: &["name"],
+        recommended: &["url", "logo", "sameAs", "address", "contactPoint"],
+    },
+    TypeRule {
+        name: "Product",
+        subtypes: &[],
+        required: &["name"],
+        recommended: &[
+            "image",
+            "description",
+            "offers",
+            "brand",
+            "sku",
+            "aggregateRating",
+            "review",
+        ],
+    },
+];
+
+// 検証結果
+#[derive(Debug, Clone)]
+pub struct SchemaReport {
+    pub entity: usize,
+    pub rule: &'static str,
+    pub missing_required: Vec<&'static str>,
+    pub missing_recommended: Vec<&'static str>,
+}
+
+impl SchemaReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing_required.is_empty()
+    }
+}
+
+// "https://schema.org/Article" などを "Article" にする
+fn short_type(value: &str) -> String {
+    value
+        .trim()
+        .rsplit(['/', '#', ':'])
+        .next()
+        .unwrap_or("")
+        .to_string()
+}
+
+fn normalize(text: &str) -> String {
+    text.replace_ws().format_ws()
+}
+
+impl StructuredData {
+    fn push(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    // JSON-LDのオブジェクトをエンティティに変換する
+    fn add_json(&mut self, value: &Value, nested: bool) -> Option<PropValue> {
+        match value {
+            Value::Object(map) => {
+                if let Some(graph) = map.get("@graph") {
+                    self.add_json(graph, nested);
+                    if !map.contains_key("@type") {
+                        return None;
+                    }
+                }
+                let types = match map.get("@type") {
+                    Some(Value::String(t)) => vec![short_type(t)],
+                    Some(Value::Array(ts)) => ts
+                        .iter()
+                        .filter_map(|t| t.as_str().map(short_type))
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let index = self.push(Entity {
+                    id: map
+                        .get("@id")
+                        .and_then(|id| id.as_str())
+                        .map(str::to_string),
+                    types,
+                    properties: BTreeMap::new(),
+                    syntax: Syntax::JsonLd,
+                    nested,
+                });
+                for (key, child) in map {
+                    if key.starts_with('@') {
+                        continue;
+                    }
+                    let children = match child {
+                        Value::Array(items) => items.iter().collect(),
+                        _ => vec![child],
+                    };
+                    for item in children {
+                        if let Some(prop) = self.add_json(item, true) {
+                            self.entities[index]
+                                .properties
+                                .entry(key.clone())
+                                .or_default()
+                                .push(prop);
+                        }
+                    }
+                }
+                Some(PropValue::Entity(index))
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.add_json(item, nested);
+                }
+                None
+            }
+            Value::String(text) => Some(PropValue::Text(normalize(text))),
+            Value::Number(number) => Some(PropValue::Text(number.to_string())),
+            Value::Bool(flag) => Some(PropValue::Text(flag.to_string())),
+            Value::Null => None,
+        }
+    }
+
+    // 同じ@idのエンティティを1つにまとめ、参照をまとめた先に付け替える
+    // ({"@id": "#person"} だけの参照と@graph内の定義などを結びつける)
+    fn merge_ids(&mut self) {
+        let mut first_by_id: HashMap<String, usize> = HashMap::new();
+        let target: Vec<usize> = self
+            .entities
+            .iter()
+            .enumerate()
+            .map(|(index, entity)| match &entity.id {
+                Some(id) => *first_by_id.entry(id.clone()).or_insert(index),
+                None => index,
+            })
+            .collect();
+        for (index, &first) in target.iter().enumerate() {
+            if first == index {
+                continue;
+            }
+            let entity = self.entities[index].clone();
+            let merged = &mut self.entities[first];
+            for t in entity.types {
+                if !merged.types.contains(&t) {
+                    merged.types.push(t);
+                }
+            }
+            for (key, values) in entity.properties {
+                let existing = merged.properties.entry(key).or_default();
+                for value in values {
+                    if !existing.contains(&value) {
+                        existing.push(value);
+                    }
+                }
+            }
+            // どこかで最上位に定義されていれば最上位とみなす
+            merged.nested = merged.nested && entity.nested;
+        }
+
+        // まとめられた側を取り除いた後の位置
+        let mut new_index = vec![0; self.entities.len()];
+        let mut kept = 0;
+        for (index, &merged) in target.iter().enumerate() {
+            if merged == index {
+                new_index[index] = kept;
+                kept += 1;
+            }
+        }
+        let entities = std::mem::take(&mut self.entities);
+        self.entities = entities
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| target[*index] == *index)
+            .map(|(_, mut entity)| {
+                for values in entity.properties.values_mut() {
+                    for value in values.iter_mut() {
+                        if let PropValue::Entity(referenced) = value {
+                            *referenced = new_index[target[*referenced]];
+                        }
+                    }
+                    let mut unique: Vec<PropValue> = Vec::new();
+                    for value in values.drain(..) {
+                        if !unique.contains(&value) {
+                            unique.push(value);
+                        }
+                    }
+                    *values = unique;
+                }
+                entity
+            })
+            .collect();
+    }
+
+    // 要素の属性からエンティティを組み立てる (Microdata / RDFa Lite共通)
+    fn add_element(&mut self, element: &ElementRef, syntax: Syntax, nested: bool) -> usize {
+        let value = element.value();
+        let (type_attr, id_attr) = match syntax {
+            Syntax::Microdata => ("itemtype", "itemid"),
+            _ => ("typeof", "resource"),
+        };
+        let index = self.push(Entity {
+            id: value.attr(id_attr).map(str::to_string),
+            types: value
+                .attr(type_attr)
+                .unwrap_or("")
+                .split_whitespace()
+                .map(short_type)
+                .collect(),
+            properties: BTreeMap::new(),
+            syntax,
+            nested,
+        });
+
+        for child in element.descendants().filter_map(ElementRef::wrap) {
+            if child.id() == element.id() || !belongs_to(&child, element, syntax) {
+                continue;
+            }
+            let Some(names) = child.value().attr(prop_attr(syntax)) else {
+                continue;
+            };
+            let prop = if is_scope(&child, syntax) {
+                PropValue::Entity(self.add_element(&child, syntax, true))
+            } else {
+                PropValue::Text(element_value(&child))
+            };
+            for name in names.split_whitespace() {
+                self.entities[index]
+                    .properties
+                    .entry(short_type(name))
+                    .or_default()
+                    .push(prop.clone());
+            }
+        }
+        index
+    }
+}
+
+fn prop_attr(syntax: Syntax) -> &'static str {
+    match syntax {
+        Syntax::Microdata => "itemprop",
+        _ => "property",
+    }
+}
+
+fn is_scope(element: &ElementRef, syntax: Syntax) -> bool {
+    match syntax {
+        Syntax::Microdata => element.value().attr("itemscope").is_some(),
+        _ => element.value().attr("typeof").is_some(),
+    }
+}
+
+// プロパティが直近のエンティティ要素に属しているか
+fn belongs_to(element: &ElementRef, scope: &ElementRef, syntax: Syntax) -> bool {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|ancestor| is_scope(ancestor, syntax))
+        .is_some_and(|ancestor| ancestor.id() == scope.id())
+}
+
+// プロパティ要素の値を取り出す
+fn element_value(element: &ElementRef) -> String {
+    let value = element.value();
+    let attr = match value.name() {
+        "meta" => value.attr("content"),
+        "a" | "link" | "area" => value.attr("href"),
+        "img" | "audio" | "video" | "source" | "iframe" | "embed" => value.attr("src"),
+        "time" => value.attr("datetime"),
+        "data" | "meter" => value.attr("value"),
+        _ => None,
+    };
+    let text = value
+        .attr("content")
+        .or(value.attr("resource"))
+        .or(attr)
+        .map(str::to_string)
+        .unwrap_or_else(|| element.text().collect::<String>());
+    normalize(&text)
+}
+
+pub fn extract_html(html_content: &str) -> StructuredData {
+    let document = Html::parse_document(html_content);
+    let mut data = StructuredData::default();
+
+    let json_selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    for script in document.select(&json_selector) {
+        let text = script.text().collect::<String>();
+        if let Ok(value) = serde_json::from_str::<Value>(text.trim()) {
+            data.add_json(&value, false);
+        }
+    }
+
+    for syntax in [Syntax::Microdata, Syntax::Rdfa] {
+        // 他のエンティティのプロパティになっていない最上位の要素から辿る
+        let roots: Vec<ElementRef> = document
+            .tree
+            .nodes()
+            .filter_map(ElementRef::wrap)
+            .filter(|element| {
+                is_scope(element, syntax) && element.value().attr(prop_attr(syntax)).is_none()
+            })
+            .collect();
+        for root in roots {
+            data.add_element(&root, syntax, false);
+        }
+    }
+    data.merge_ids();
+    data
+}
+
+pub fn extract(file_path: &str) -> StructuredData {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    extract_html(&html_content)
+}
+
+impl StructuredData {
+    pub fn has_type(&self, type_name: &str) -> bool {
+        self.entities
+            .iter()
+            .any(|entity| entity.types.iter().any(|t| t == type_name))
+    }
+
+    // 他のエンティティのプロパティとしてだけ出現するものを除いた数
+    pub fn top_level_count(&self) -> usize {
+        self.entities.iter().filter(|entity| !entity.nested).count()
+    }
+
+    pub fn types(&self) -> Vec<String> {
+        let mut types: Vec<String> = self
+            .entities
+            .iter()
+            .flat_map(|entity| entity.types.clone())
+            .collect();
+        types.sort();
+        types.dedup();
+        types
+    }
+
+    // 埋め込みの型ルールで必須・推奨プロパティを確認する
+    pub fn validate(&self) -> Vec<SchemaReport> {
+        let mut reports = Vec::new();
+        for (index, entity) in self.entities.iter().enumerate() {
+            for rule in &TYPE_RULES {
+                let matched = entity
+                    .types
+                    .iter()
+                    .any(|t| t == rule.name || rule.subtypes.contains(&t.as_str()));
+                if !matched {
+                    continue;
+                }
+                let missing = |props: &'static [&'static str]| -> Vec<&'static str> {
+                    props
+                        .iter()
+                        .filter(|prop| !entity.properties.contains_key(**prop))
+                        .copied()
+                        .collect()
+                };
+                reports.push(SchemaReport {
+                    entity: index,
+                    rule: rule.name,
+                    missing_required: missing(rule.required),
+                    missing_recommended: missing(rule.recommended),
+                });
+            }
+        }
+        reports
+    }
+
+    // 必須プロパティを満たしている検索機能向けの型の数
+    pub fn valid_rule_count(&self) -> usize {
+        let mut rules: Vec<&str> = self
+            .validate()
+            .into_iter()
+            .filter(|report| report.is_valid())
+            .map(|report| report.rule)
+            .collect();
+        rules.sort();
+        rules.dedup();
+        rules.len()
+    }
+}
@@ -0,0 +1,260 @@
+use crate::mods::corpus;
+use crate::mods::main_content::{self, BlockKind};
+use crate::mods::text_quality::split_sentences;
+use crate::mods::tokenaize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+// 文の重要度の求め方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankMethod {
+    TextRank, // 共通する語の数で文同士を結ぶ
+    #[default]
+    LexRank, // TF-IDFのコサイン類似度で文同士を結ぶ
+}
+
+#[derive(Debug, Clone)]
+pub struct SummaryConfig {
+    pub sentences: usize,
+    pub method: RankMethod,
+    pub damping: f64,
+    pub iterations: usize,
+    pub tolerance: f64,
+    pub min_chars: usize, // これより短い文 (見出しの断片など) は候補にしない
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        SummaryConfig {
+            sentences: 3,
+            method: RankMethod::LexRank,
+            damping: 0.85,
+            iterations: 100,
+            tolerance: 1e-6,
+            min_chars: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RankedSentence {
+    pub index: usize, // 文書内の順番
+    pub text: String,
+    pub score: f64,
+}
+
+// 重要な文を文書内の順番で並べたもの
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub sentences: Vec<RankedSentence>,
+    pub total_sentences: usize,
+}
+
+impl Summary {
+    pub fn text(&self) -> String {
+        self.sentences
+            .iter()
+            .map(|sentence| sentence.text.as_str())
+            .collect()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.sentences
+            .iter()
+            .map(|sentence| sentence.text.clone())
+            .collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        self.sentences
+            .iter()
+            .map(|sentence| format!("- {}\n", sentence.text))
+            .collect()
+    }
+}
+
+// TextRankの類似度: 共通語数 / (ln|Si| + ln|Sj|)
+fn overlap_similarity(a: &HashSet<&String>, b: &HashSet<&String>) -> f64 {
+    let denominator = (a.len() as f64).ln() + (b.len() as f64).ln();
+    if denominator <= 0.0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / denominator
+}
+
+fn cosine_similarity(a: &HashMap<&String, f64>, b: &HashMap<&String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, weight)| b.get(term).map(|other| weight * other))
+        .sum();
+    let norm = |vector: &HashMap<&String, f64>| vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+// 文ごとのTF-IDF (IDFは文を文書とみなして求める)
+fn tf_idf_vectors(tokens: &[Vec<String>]) -> Vec<HashMap<&String, f64>> {
+    let mut df: HashMap<&String, usize> = HashMap::new();
+    for sentence in tokens {
+        let seen: HashSet<&String> = sentence.iter().collect();
+        for term in seen {
+            *df.entry(term).or_insert(0) += 1;
+        }
+    }
+    let n = tokens.len() as f64;
+    tokens
+        .iter()
+        .map(|sentence| {
+            let mut vector: HashMap<&String, f64> = HashMap::new();
+            for term in sentence {
+                *vector.entry(term).or_insert(0.0) += 1.0;
+            }
+            for (term, tf) in vector.iter_mut() {
+                *tf *= (n / df[term] as f64).ln() + 1.0;
+            }
+            vector
+        })
+        .collect()
+}
+
+fn similarity_matrix(tokens: &[Vec<String>], method: RankMethod) -> Vec<Vec<f64>> {
+    let n = tokens.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    match method {
+        RankMethod::TextRank => {
+            let sets: Vec<HashSet<&String>> = tokens.iter().map(|s| s.iter().collect()).collect();
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let similarity = overlap_similarity(&sets[i], &sets[j]);
+                    matrix[i][j] = similarity;
+                    matrix[j][i] = similarity;
+                }
+            }
+        }
+        RankMethod::LexRank => {
+            let vectors = tf_idf_vectors(tokens);
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let similarity = cosine_similarity(&vectors[i], &vectors[j]);
+                    matrix[i][j] = similarity;
+                    matrix[j][i] = similarity;
+                }
+            }
+        }
+    }
+    matrix
+}
+
+// 類似度で重み付けしたPageRank
+fn page_rank(matrix: &[Vec<f64>], config: &SummaryConfig) -> Vec<f64> {
+    let n = matrix.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let out_weights: Vec<f64> = matrix.iter().map(|row| row.iter().sum()).collect();
+    let mut scores = vec![1.0 / n as f64; n];
+    for _ in 0..config.iterations {
+        let next: Vec<f64> = (0..n)
+            .map(|i| {
+                let incoming: f64 = (0..n)
+                    .filter(|&j| out_weights[j] > 0.0)
+                    .map(|j| matrix[j][i] / out_weights[j] * scores[j])
+                    .sum();
+                (1.0 - config.damping) / n as f64 + config.damping * incoming
+            })
+            .collect();
+        let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if change < config.tolerance {
+            break;
+        }
+    }
+    scores
+}
+
+// 文ごとの重要度
+pub fn rank_sentences(sentences: &[String], config: &SummaryConfig) -> Vec<f64> {
+    let tokens: Vec<Vec<String>> = sentences
+        .iter()
+        .map(|sentence| tokenaize::tokenize_word(sentence))
+        .collect();
+    page_rank(&similarity_matrix(&tokens, config.method), config)
+}
+
+pub fn summarize_text(text: &str, config: &SummaryConfig) -> Summary {
+    let sentences: Vec<String> = split_sentences(text)
+        .into_iter()
+        .filter(|sentence| sentence.chars().count() >= config.min_chars)
+        .collect();
+    let scores = rank_sentences(&sentences, config);
+    let mut ranked: Vec<RankedSentence> = sentences
+        .iter()
+        .zip(scores)
+        .enumerate()
+        .map(|(index, (text, score))| RankedSentence {
+            index,
+            text: text.clone(),
+            score,
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.index.cmp(&b.index)));
+    ranked.truncate(config.sentences);
+    ranked.sort_by_key(|sentence| sentence.index);
+    Summary {
+        sentences: ranked,
+        total_sentences: sentences.len(),
+    }
+}
+
+// 本文の段落 (見出しを除く) を要約する
+pub fn summarize(file_path: &str, config: &SummaryConfig) -> Summary {
+    let paragraphs: Vec<String> = main_content::extract_main(file_path)
+        .blocks
+        .into_iter()
+        .filter(|block| block.kind == BlockKind::Text)
+        .map(|block| block.text)
+        .collect();
+    summarize_text(&paragraphs.join("\n"), config)
+}
+
+// 保存済みのページを順位で指定して要約する
+pub fn summarize_rank(rank: usize, config: &SummaryConfig) -> Option<Summary> {
+    let path = corpus::page_path(rank);
+    Path::new(&path).exists().then(|| summarize(&path, config))
+}
+
+// 上位ページそれぞれの要約
+pub fn top_summaries(config: &SummaryConfig) -> Vec<(usize, Summary)> {
+    corpus::TOP_RANKS
+        .filter_map(|rank| Some((rank, summarize_rank(rank, config)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 対称なグラフではどの文も同じ重要度になる
+    #[test]
+    fn page_rank_symmetric_graph() {
+        let matrix = vec![
+            vec![0.0, 1.0, 1.0],
+            vec![1.0, 0.0, 1.0],
+            vec![1.0, 1.0, 0.0],
+        ];
+        let scores = page_rank(&matrix, &SummaryConfig::default());
+        assert_eq!(scores.len(), 3);
+        for score in scores {
+            assert!((score - 1.0 / 3.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn page_rank_empty_graph() {
+        assert!(page_rank(&[], &SummaryConfig::default()).is_empty());
+    }
+}
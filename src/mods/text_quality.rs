@@ -0,0 +1,311 @@
+use crate::mods::main_content::{self, BlockKind};
+use crate::mods::statistics;
+use crate::mods::tokenaize;
+use std::collections::{HashMap, HashSet};
+
+// 文の区切り
+const SENTENCE_ENDS: [char; 5] = ['。', '！', '？', '!', '?'];
+// 文の長さの分布の区切り (文字数)
+pub const LENGTH_BINS: [usize; 4] = [20, 40, 60, 80];
+// 受身の助動詞 (活用形を含む)
+const PASSIVE_TOKENS: [&str; 6] = ["れる", "られる", "れ", "られ", "れれ", "られれ"];
+// 丁寧語・尊敬語・謙譲語の目印
+const HONORIFIC_TOKENS: [&str; 16] = [
+    "です",
+    "でし",
+    "ます",
+    "まし",
+    "ませ",
+    "ござい",
+    "いただく",
+    "いただき",
+    "いただけ",
+    "くださる",
+    "ください",
+    "くださっ",
+    "いらっしゃる",
+    "いらっしゃい",
+    "おっしゃる",
+    "申し",
+];
+// 繰り返しとみなす語句の長さと回数
+const PHRASE_LENGTH: usize = 4;
+const MIN_REPEAT: usize = 3;
+// MTLDの基準値
+const MTLD_THRESHOLD: f64 = 0.72;
+
+// 文字種
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharKind {
+    Kanji,
+    Hiragana,
+    Katakana,
+    Alphabet,
+    Other,
+}
+
+pub fn char_kind(c: char) -> CharKind {
+    match c {
+        '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '々' | '〆' => CharKind::Kanji,
+        '\u{3041}'..='\u{309F}' => CharKind::Hiragana,
+        '\u{30A0}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9F}' => CharKind::Katakana,
+        'a'..='z' | 'A'..='Z' | 'ａ'..='ｚ' | 'Ａ'..='Ｚ' => CharKind::Alphabet,
+        _ => CharKind::Other,
+    }
+}
+
+// 文の長さの分布
+#[derive(Debug, Clone, Default)]
+pub struct SentenceStats {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub max: usize,
+    pub std_dev: f64,
+    pub histogram: Vec<usize>, // LENGTH_BINSで区切った文の数 (最後は80文字以上)
+}
+
+// 文章の品質の指標
+#[derive(Debug, Clone, Default)]
+pub struct TextQuality {
+    pub characters: usize,
+    pub sentences: SentenceStats,
+    pub kanji_ratio: f64,
+    pub hiragana_ratio: f64,
+    pub katakana_ratio: f64,
+    pub alphabet_ratio: f64,
+    pub readability: f64, // 建石の式 (大きいほど読みやすい, 50前後が標準)
+    pub paragraphs: usize,
+    pub mean_paragraph_length: f64,
+    pub passive_density: f64,   // 1文あたりの受身表現の数
+    pub honorific_density: f64, // 1文あたりの敬語表現の数
+    pub repeated_phrases: Vec<(String, usize)>,
+    pub ttr: f64,
+    pub mtld: f64,
+}
+
+// 。！？と改行で文に分ける
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if c == '\n' || c == '\r' {
+            sentences.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+        if SENTENCE_ENDS.contains(&c) {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    sentences.push(current);
+    sentences
+        .into_iter()
+        .map(|sentence| sentence.trim().to_string())
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
+
+fn sentence_stats(sentences: &[String]) -> SentenceStats {
+    let mut lengths: Vec<usize> = sentences.iter().map(|s| s.chars().count()).collect();
+    if lengths.is_empty() {
+        return SentenceStats::default();
+    }
+    lengths.sort();
+    let count = lengths.len();
+    let mean = lengths.iter().sum::<usize>() as f64 / count as f64;
+    let variance = lengths
+        .iter()
+        .map(|&length| (length as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count as f64;
+    let median = statistics::median(&lengths.iter().map(|&l| l as f64).collect::<Vec<f64>>());
+    let mut histogram = vec![0; LENGTH_BINS.len() + 1];
+    for &length in &lengths {
+        let bin = LENGTH_BINS
+            .iter()
+            .position(|&limit| length < limit)
+            .unwrap_or(LENGTH_BINS.len());
+        histogram[bin] += 1;
+    }
+    SentenceStats {
+        count,
+        mean,
+        median,
+        max: lengths[count - 1],
+        std_dev: variance.sqrt(),
+        histogram,
+    }
+}
+
+// 同じ文字種が続く部分 (連) に分ける
+fn char_runs(text: &str) -> Vec<(CharKind, usize)> {
+    let mut runs: Vec<(CharKind, usize)> = Vec::new();
+    for c in text.chars() {
+        let kind = char_kind(c);
+        match runs.last_mut() {
+            Some((last, length)) if *last == kind => *length += 1,
+            _ => runs.push((kind, 1)),
+        }
+    }
+    runs
+}
+
+// 建石・小野・山田 (1988) の簡略化した式 (RS')
+pub fn tateishi_score(sentences: &[String]) -> f64 {
+    let text = sentences.concat();
+    let runs: Vec<(CharKind, usize)> = char_runs(&text)
+        .into_iter()
+        .filter(|(kind, _)| *kind != CharKind::Other)
+        .collect();
+    if runs.is_empty() || sentences.is_empty() {
+        return 0.0;
+    }
+    // 文字種ごとの連の平均の長さ
+    let mean_run = |kind: CharKind| {
+        let lengths: Vec<usize> = runs
+            .iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, length)| *length)
+            .collect();
+        if lengths.is_empty() {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+        }
+    };
+    let ls = text.chars().count() as f64 / sentences.len() as f64;
+    let commas = text.chars().filter(|&c| c == '、' || c == '，').count();
+    let cp = commas as f64 / sentences.len() as f64;
+    -0.12 * ls - 1.37 * mean_run(CharKind::Alphabet) + 7.4 * mean_run(CharKind::Hiragana)
+        - 23.18 * mean_run(CharKind::Kanji)
+        - 5.4 * mean_run(CharKind::Katakana)
+        - 4.67 * cp
+        + 115.79
+}
+
+// 一定回数以上出現するPHRASE_LENGTH語の連なり
+fn repeated_phrases(tokens: &[String]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for window in tokens.windows(PHRASE_LENGTH) {
+        if window.iter().any(|token| token.trim().is_empty()) {
+            continue;
+        }
+        *counts.entry(window.concat()).or_insert(0) += 1;
+    }
+    let mut phrases: Vec<(String, usize)> = counts
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_REPEAT)
+        .collect();
+    phrases.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    phrases
+}
+
+pub fn type_token_ratio(tokens: &[String]) -> f64 {
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    let types: HashSet<&String> = tokens.iter().collect();
+    types.len() as f64 / tokens.len() as f64
+}
+
+// TTRが基準値を下回るまでを1区切りとして数える
+fn mtld_pass<'a>(tokens: impl Iterator<Item = &'a String>) -> f64 {
+    let mut factors = 0.0;
+    let mut types = HashSet::new();
+    let mut count = 0;
+    let mut ttr = 1.0;
+    for token in tokens {
+        types.insert(token);
+        count += 1;
+        ttr = types.len() as f64 / count as f64;
+        if ttr <= MTLD_THRESHOLD {
+            factors += 1.0;
+            types.clear();
+            count = 0;
+            ttr = 1.0;
+        }
+    }
+    // 途中で終わった区切りは達成度に応じて加える
+    if count > 0 {
+        factors += (1.0 - ttr) / (1.0 - MTLD_THRESHOLD);
+    }
+    factors
+}
+
+pub fn mtld(tokens: &[String]) -> f64 {
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    let forward = mtld_pass(tokens.iter());
+    let backward = mtld_pass(tokens.iter().rev());
+    let measure = |factors: f64| {
+        if factors > 0.0 {
+            tokens.len() as f64 / factors
+        } else {
+            tokens.len() as f64
+        }
+    };
+    (measure(forward) + measure(backward)) / 2.0
+}
+
+// 段落のテキストから指標を求める
+pub fn analyze_paragraphs(paragraphs: &[String]) -> TextQuality {
+    let text = paragraphs.join("\n");
+    let sentences = split_sentences(&text);
+    let sentence_count = sentences.len().max(1) as f64;
+
+    let letters: Vec<CharKind> = text
+        .chars()
+        .map(char_kind)
+        .filter(|kind| *kind != CharKind::Other)
+        .collect();
+    let ratio = |kind: CharKind| {
+        if letters.is_empty() {
+            return 0.0;
+        }
+        letters.iter().filter(|k| **k == kind).count() as f64 / letters.len() as f64
+    };
+
+    let surface = tokenaize::tokenize_text(&text);
+    let count_in = |targets: &[&str]| {
+        surface
+            .iter()
+            .filter(|token| targets.contains(&token.as_str()))
+            .count() as f64
+    };
+    let words = tokenaize::tokenize_word(&text);
+
+    TextQuality {
+        characters: text.chars().filter(|c| !c.is_whitespace()).count(),
+        sentences: sentence_stats(&sentences),
+        kanji_ratio: ratio(CharKind::Kanji),
+        hiragana_ratio: ratio(CharKind::Hiragana),
+        katakana_ratio: ratio(CharKind::Katakana),
+        alphabet_ratio: ratio(CharKind::Alphabet),
+        readability: tateishi_score(&sentences),
+        paragraphs: paragraphs.len(),
+        mean_paragraph_length: if paragraphs.is_empty() {
+            0.0
+        } else {
+            paragraphs.iter().map(|p| p.chars().count()).sum::<usize>() as f64
+                / paragraphs.len() as f64
+        },
+        passive_density: count_in(&PASSIVE_TOKENS) / sentence_count,
+        honorific_density: count_in(&HONORIFIC_TOKENS) / sentence_count,
+        repeated_phrases: repeated_phrases(&surface),
+        ttr: type_token_ratio(&words),
+        mtld: mtld(&words),
+    }
+}
+
+// 本文の段落 (見出しを除く) を対象にする
+pub fn analyze(file_path: &str) -> TextQuality {
+    let paragraphs: Vec<String> = main_content::extract_main(file_path)
+        .blocks
+        .into_iter()
+        .filter(|block| block.kind == BlockKind::Text)
+        .map(|block| block.text)
+        .collect();
+    analyze_paragraphs(&paragraphs)
+}
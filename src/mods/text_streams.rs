@@ -0,0 +1,116 @@
+use crate::mods::main_content::{self, SKIP_TAGS};
+use crate::mods::traits::Formatter;
+use scraper::{ElementRef, Html, Node};
+
+// テキストを種類ごとに分けたもの
+#[derive(Debug, Clone, Default)]
+pub struct TextStreams {
+    pub anchor: Vec<String>, // aタグ内のテキスト
+    pub body: Vec<String>,   // aタグ以外の表示テキスト
+    pub alt: Vec<String>,    // alt属性のテキスト
+    pub hidden: Vec<String>, // 非表示要素内のテキスト
+}
+
+// 1種類のテキストの集計
+#[derive(Debug, Clone, Default)]
+pub struct StreamStats {
+    pub chars: usize,
+    pub first_query: usize,
+    pub second_query: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StreamReport {
+    pub anchor: StreamStats,
+    pub body: StreamStats,
+    pub alt: StreamStats,
+    pub hidden: StreamStats,
+}
+
+fn push_text(stream: &mut Vec<String>, text: &str) {
+    let text = text.replace_ws().format_ws();
+    if !text.is_empty() {
+        stream.push(text);
+    }
+}
+
+fn walk(element: &ElementRef, in_anchor: bool, in_hidden: bool, streams: &mut TextStreams) {
+    let name = element.value().name();
+    let in_anchor = in_anchor || name == "a";
+    let in_hidden = in_hidden || main_content::is_hidden(element);
+
+    if let Some(alt) = element.value().attr("alt") {
+        if matches!(name, "img" | "area" | "input") {
+            if in_hidden {
+                push_text(&mut streams.hidden, alt);
+            } else {
+                push_text(&mut streams.alt, alt);
+            }
+        }
+    }
+
+    for child in element.children() {
+        match child.value() {
+            Node::Text(content) => {
+                if in_hidden {
+                    push_text(&mut streams.hidden, content);
+                } else if in_anchor {
+                    push_text(&mut streams.anchor, content);
+                } else {
+                    push_text(&mut streams.body, content);
+                }
+            }
+            Node::Element(child_element) => {
+                if SKIP_TAGS.contains(&child_element.name()) {
+                    continue;
+                }
+                if let Some(child_ref) = ElementRef::wrap(child) {
+                    walk(&child_ref, in_anchor, in_hidden, streams);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn split_streams_html(html_content: &str) -> TextStreams {
+    let document = Html::parse_document(html_content);
+    let mut streams = TextStreams::default();
+    walk(&document.root_element(), false, false, &mut streams);
+    streams
+}
+
+pub fn split_streams(file_path: &str) -> TextStreams {
+    let html_content = std::fs::read_to_string(file_path).expect("Failed to read file");
+    split_streams_html(&html_content)
+}
+
+// 空白を除いた文字数とクエリの使用回数を数える
+fn stream_stats(texts: &[String], first_query: &str, second_query: &str) -> StreamStats {
+    let count = |query: &str| -> usize {
+        if query.is_empty() {
+            0
+        } else {
+            texts.iter().map(|text| text.matches(query).count()).sum()
+        }
+    };
+    StreamStats {
+        chars: texts
+            .iter()
+            .map(|text| text.chars().filter(|c| !c.is_whitespace()).count())
+            .sum(),
+        first_query: count(first_query),
+        second_query: count(second_query),
+    }
+}
+
+impl TextStreams {
+    pub fn report(&self, first_query: &str, second_query: &str) -> StreamReport {
+        StreamReport {
+            anchor: stream_stats(&self.anchor, first_query, second_query),
+            body: stream_stats(&self.body, first_query, second_query),
+            alt: stream_stats(&self.alt, first_query, second_query),
+            hidden: stream_stats(&self.hidden, first_query, second_query),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::mods::corpus::Page;
+use crate::mods::corpus_stats::CorpusStats;
+use crate::mods::dom_counter;
+use crate::mods::features::{self, PageFeatures};
+use crate::mods::statistics;
+use std::collections::{BTreeSet, HashMap};
+
+// 事前分布の強さ (背景コーパスを何語分として扱うか)
+pub const PRIOR_SCALE: f64 = 500.0;
+
+// 語の出現差 (正ならa側に多い)
+#[derive(Debug, Clone, Default)]
+pub struct TermDiff {
+    pub term: String,
+    pub count_a: usize,
+    pub count_b: usize,
+    pub log_odds: f64,
+    pub z: f64,
+    pub p_value: f64,
+}
+
+// 数値特徴量の分布差 (正ならa側が大きい)
+#[derive(Debug, Clone, Default)]
+pub struct FeatureDiff {
+    pub name: String,
+    pub median_a: f64,
+    pub median_b: f64,
+    pub effect_size: f64,
+    pub p_value: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TierReport {
+    pub terms: Vec<TermDiff>,
+    pub tags: Vec<FeatureDiff>,
+    pub features: Vec<FeatureDiff>,
+}
+
+fn term_counts(pages: &[Page]) -> HashMap<String, usize> {
+    let tokens: Vec<String> = pages.iter().flat_map(|page| page.tokens()).collect();
+    statistics::word_distribution(tokens).into_iter().collect()
+}
+
+// 情報事前分布付きディリクレモデルの対数オッズ比で語の差を求める
+pub fn term_log_odds(a: &[Page], b: &[Page]) -> Vec<TermDiff> {
+    let counts_a = term_counts(a);
+    let counts_b = term_counts(b);
+    let n_a = counts_a.values().sum::<usize>() as f64;
+    let n_b = counts_b.values().sum::<usize>() as f64;
+    let n_all = (n_a + n_b).max(1.0);
+
+    let terms: BTreeSet<&String> = counts_a.keys().chain(counts_b.keys()).collect();
+    let mut result: Vec<TermDiff> = terms
+        .into_iter()
+        .map(|term| {
+            let y_a = counts_a.get(term).copied().unwrap_or(0);
+            let y_b = counts_b.get(term).copied().unwrap_or(0);
+            let alpha = PRIOR_SCALE * (y_a + y_b) as f64 / n_all;
+            let odds_a = (y_a as f64 + alpha) / (n_a + PRIOR_SCALE - y_a as f64 - alpha);
+            let odds_b = (y_b as f64 + alpha) / (n_b + PRIOR_SCALE - y_b as f64 - alpha);
+            let log_odds = odds_a.ln() - odds_b.ln();
+            let variance = 1.0 / (y_a as f64 + alpha) + 1.0 / (y_b as f64 + alpha);
+            let z = log_odds / variance.sqrt();
+            TermDiff {
+                term: term.clone(),
+                count_a: y_a,
+                count_b: y_b,
+                log_odds,
+                z,
+                p_value: statistics::two_sided_p(z),
+            }
+        })
+        .collect();
+    result.sort_by(|x, y| y.log_odds.abs().total_cmp(&x.log_odds.abs()));
+    result
+}
+
+// 名前付きの数値列をMann-WhitneyのU検定で比べる
+fn compare_columns(names: &[String], a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<FeatureDiff> {
+    let mut result: Vec<FeatureDiff> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let column_a: Vec<f64> = a.iter().map(|row| row[i]).collect();
+            let column_b: Vec<f64> = b.iter().map(|row| row[i]).collect();
+            let test = statistics::mann_whitney_u(&column_a, &column_b);
+            FeatureDiff {
+                name: name.clone(),
+                median_a: statistics::median(&column_a),
+                median_b: statistics::median(&column_b),
+                effect_size: test.effect_size,
+                p_value: test.p_value,
+            }
+        })
+        .collect();
+    result.sort_by(|x, y| y.effect_size.abs().total_cmp(&x.effect_size.abs()));
+    result
+}
+
+// タグごとの使用数の差
+pub fn tag_differences(a: &[Page], b: &[Page]) -> Vec<FeatureDiff> {
+    let counts_a: Vec<HashMap<String, i32>> = a
+        .iter()
+        .map(|page| dom_counter::count_tags(&page.path))
+        .collect();
+    let counts_b: Vec<HashMap<String, i32>> = b
+        .iter()
+        .map(|page| dom_counter::count_tags(&page.path))
+        .collect();
+
+    let tags: Vec<String> = counts_a
+        .iter()
+        .chain(counts_b.iter())
+        .flat_map(|counts| counts.keys().cloned())
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .collect();
+    let to_rows = |counts: &[HashMap<String, i32>]| -> Vec<Vec<f64>> {
+        counts
+            .iter()
+            .map(|count| {
+                tags.iter()
+                    .map(|tag| count.get(tag).copied().unwrap_or(0) as f64)
+                    .collect()
+            })
+            .collect()
+    };
+    compare_columns(&tags, &to_rows(&counts_a), &to_rows(&counts_b))
+}
+
+// ランキング特徴量の差
+pub fn feature_differences(
+    a: &[Page],
+    b: &[Page],
+    main_query: &str,
+    sub_query: &str,
+) -> Vec<FeatureDiff> {
+    let all_pages: Vec<Page> = a.iter().chain(b.iter()).cloned().collect();
+    let stats = CorpusStats::from_pages(&all_pages);
+    let to_rows = |pages: &[Page]| -> Vec<Vec<f64>> {
+        pages
+            .iter()
+            .map(|page| features::page_features(&stats, page, main_query, sub_query).to_vec())
+            .collect()
+    };
+    let names: Vec<String> = PageFeatures::names()
+        .into_iter()
+        .map(|name| name.to_string())
+        .collect();
+    compare_columns(&names, &to_rows(a), &to_rows(b))
+}
+
+// 上位と下位などの順位帯を比較する
+pub fn compare(a: &[Page], b: &[Page], main_query: &str, sub_query: &str) -> TierReport {
+    TierReport {
+        terms: term_log_odds(a, b),
+        tags: tag_differences(a, b),
+        features: feature_differences(a, b, main_query, sub_query),
+    }
+}
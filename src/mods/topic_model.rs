@@ -0,0 +1,254 @@
+use crate::mods::corpus::Page;
+use ndarray::{Array1, Array2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+// LDAの学習設定
+#[derive(Debug, Clone)]
+pub struct LdaConfig {
+    pub topics: usize,
+    pub alpha: f64,
+    pub beta: f64,
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl Default for LdaConfig {
+    fn default() -> Self {
+        LdaConfig {
+            topics: 8,
+            alpha: 0.1,
+            beta: 0.01,
+            iterations: 500,
+            seed: 0,
+        }
+    }
+}
+
+// 崩壊型ギブスサンプリングで学習したLDAモデル
+#[derive(Debug, Clone)]
+pub struct Lda {
+    pub config: LdaConfig,
+    pub vocab: Vec<String>,
+    pub word_index: HashMap<String, usize>,
+    pub topic_word: Array2<f64>, // トピックごとの語の割当数 (K x V)
+    pub doc_topic: Array2<f64>,  // 文書ごとのトピックの割当数 (D x K)
+    pub topic_total: Array1<f64>,
+}
+
+// トピックごとの網羅度の比較
+#[derive(Debug, Clone, Default)]
+pub struct TopicCoverage {
+    pub topic: usize,
+    pub terms: Vec<String>,
+    pub top_share: f64,    // 上位ページの平均トピック比率
+    pub top_coverage: f64, // トピックを扱う上位ページの割合
+    pub target_share: f64,
+    pub gap: f64,
+}
+
+// 1語を割り当てるトピックをサンプリングする
+fn sample_topic(rng: &mut StdRng, weights: &[f64]) -> usize {
+    let total: f64 = weights.iter().sum();
+    let mut threshold = rng.gen::<f64>() * total;
+    for (k, weight) in weights.iter().enumerate() {
+        threshold -= weight;
+        if threshold <= 0.0 {
+            return k;
+        }
+    }
+    weights.len() - 1
+}
+
+pub fn train(pages: &[Page], config: LdaConfig) -> Lda {
+    let mut vocab = Vec::new();
+    let mut word_index = HashMap::new();
+    let docs: Vec<Vec<usize>> = pages
+        .iter()
+        .map(|page| {
+            page.tokens()
+                .into_iter()
+                .map(|token| {
+                    *word_index.entry(token.clone()).or_insert_with(|| {
+                        vocab.push(token);
+                        vocab.len() - 1
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    let topics = config.topics.max(1);
+    let vocab_size = vocab.len();
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut topic_word = Array2::<f64>::zeros((topics, vocab_size));
+    let mut doc_topic = Array2::<f64>::zeros((docs.len(), topics));
+    let mut topic_total = Array1::<f64>::zeros(topics);
+
+    // 初期のトピック割当をランダムに決める
+    let mut assignments: Vec<Vec<usize>> = docs
+        .iter()
+        .enumerate()
+        .map(|(d, doc)| {
+            doc.iter()
+                .map(|&w| {
+                    let k = rng.gen_range(0..topics);
+                    topic_word[[k, w]] += 1.0;
+                    doc_topic[[d, k]] += 1.0;
+                    topic_total[k] += 1.0;
+                    k
+                })
+                .collect()
+        })
+        .collect();
+
+    let beta_sum = config.beta * vocab_size as f64;
+    let mut weights = vec![0.0; topics];
+    for _ in 0..config.iterations {
+        for (d, doc) in docs.iter().enumerate() {
+            for (i, &w) in doc.iter().enumerate() {
+                let old = assignments[d][i];
+                topic_word[[old, w]] -= 1.0;
+                doc_topic[[d, old]] -= 1.0;
+                topic_total[old] -= 1.0;
+
+                for (k, weight) in weights.iter_mut().enumerate() {
+                    *weight = (doc_topic[[d, k]] + config.alpha)
+                        * (topic_word[[k, w]] + config.beta)
+                        / (topic_total[k] + beta_sum);
+                }
+                let new = sample_topic(&mut rng, &weights);
+
+                topic_word[[new, w]] += 1.0;
+                doc_topic[[d, new]] += 1.0;
+                topic_total[new] += 1.0;
+                assignments[d][i] = new;
+            }
+        }
+    }
+
+    Lda {
+        config: LdaConfig { topics, ..config },
+        vocab,
+        word_index,
+        topic_word,
+        doc_topic,
+        topic_total,
+    }
+}
+
+impl Lda {
+    // トピックを代表する語を確率の高い順に返す
+    pub fn top_terms(&self, topic: usize, n: usize) -> Vec<(String, f64)> {
+        let beta_sum = self.config.beta * self.vocab.len() as f64;
+        let total = self.topic_total[topic] + beta_sum;
+        let mut terms: Vec<(String, f64)> = self
+            .vocab
+            .iter()
+            .enumerate()
+            .map(|(w, term)| {
+                let prob = (self.topic_word[[topic, w]] + self.config.beta) / total;
+                (term.clone(), prob)
+            })
+            .collect();
+        terms.sort_by(|a, b| b.1.total_cmp(&a.1));
+        terms.truncate(n);
+        terms
+    }
+
+    fn normalize(&self, counts: Array1<f64>) -> Array1<f64> {
+        let total = counts.sum() + self.config.alpha * self.config.topics as f64;
+        counts.mapv(|count| (count + self.config.alpha) / total)
+    }
+
+    // 学習に使ったページのトピック比率
+    pub fn topic_mixture(&self, doc: usize) -> Array1<f64> {
+        self.normalize(self.doc_topic.row(doc).to_owned())
+    }
+
+    pub fn topic_mixtures(&self) -> Array2<f64> {
+        let mut mixtures = Array2::<f64>::zeros(self.doc_topic.dim());
+        for d in 0..self.doc_topic.nrows() {
+            mixtures.row_mut(d).assign(&self.topic_mixture(d));
+        }
+        mixtures
+    }
+
+    // 学習済みのトピックを固定して新しいページのトピック比率を推定する
+    pub fn infer(&self, page: &Page, iterations: usize) -> Array1<f64> {
+        let topics = self.config.topics;
+        let words: Vec<usize> = page
+            .tokens()
+            .iter()
+            .filter_map(|token| self.word_index.get(token).copied())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(self.config.seed);
+        let mut counts = Array1::<f64>::zeros(topics);
+        let mut assignments: Vec<usize> = words
+            .iter()
+            .map(|_| {
+                let k = rng.gen_range(0..topics);
+                counts[k] += 1.0;
+                k
+            })
+            .collect();
+
+        let beta_sum = self.config.beta * self.vocab.len() as f64;
+        let mut weights = vec![0.0; topics];
+        for _ in 0..iterations {
+            for (i, &w) in words.iter().enumerate() {
+                counts[assignments[i]] -= 1.0;
+                for (k, weight) in weights.iter_mut().enumerate() {
+                    *weight = (counts[k] + self.config.alpha)
+                        * (self.topic_word[[k, w]] + self.config.beta)
+                        / (self.topic_total[k] + beta_sum);
+                }
+                let new = sample_topic(&mut rng, &weights);
+                counts[new] += 1.0;
+                assignments[i] = new;
+            }
+        }
+        self.normalize(counts)
+    }
+}
+
+// 上位ページが扱うトピックを対象ページがどれだけ扱っているかを比べる
+pub fn coverage(
+    lda: &Lda,
+    top_pages: &[Page],
+    target: &Page,
+    min_share: f64,
+) -> Vec<TopicCoverage> {
+    let iterations = lda.config.iterations / 5;
+    let top_mixtures: Vec<Array1<f64>> = top_pages
+        .iter()
+        .map(|page| lda.infer(page, iterations))
+        .collect();
+    let target_mixture = lda.infer(target, iterations);
+    let page_count = top_mixtures.len().max(1) as f64;
+
+    let mut result: Vec<TopicCoverage> = (0..lda.config.topics)
+        .map(|k| {
+            let top_share = top_mixtures.iter().map(|mix| mix[k]).sum::<f64>() / page_count;
+            let covered = top_mixtures
+                .iter()
+                .filter(|mix| mix[k] >= min_share)
+                .count();
+            TopicCoverage {
+                topic: k,
+                terms: lda
+                    .top_terms(k, 10)
+                    .into_iter()
+                    .map(|(term, _)| term)
+                    .collect(),
+                top_share,
+                top_coverage: covered as f64 / page_count,
+                target_share: target_mixture[k],
+                gap: top_share - target_mixture[k],
+            }
+        })
+        .collect();
+    result.sort_by(|a, b| b.gap.total_cmp(&a.gap));
+    result
+}